@@ -0,0 +1,284 @@
+//! The `auth` module holds [`AuthError`], the structured payload of [`crate::prelude::Bandage::Auth`].
+use crate::prelude::{Bandage, Clean};
+
+/// The `AuthReason` enum describes why authorization failed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum AuthReason {
+    /// The request did not carry any credentials.
+    Unauthenticated,
+    /// The credentials were valid but have expired.
+    Expired,
+    /// The caller is authenticated but lacks the role or scope for the resource.
+    Forbidden,
+    /// The token is malformed, has a bad signature or was revoked.
+    InvalidToken,
+    /// The caller must complete multi-factor authentication.
+    MfaRequired,
+}
+
+impl AuthReason {
+    /// The `status_code` method returns the HTTP status code for the reason: 403 for
+    /// [`AuthReason::Forbidden`] and 401 for everything else.
+    pub fn status_code(&self) -> u16 {
+        match self {
+            Self::Forbidden => 403,
+            _ => 401,
+        }
+    }
+
+    /// The `error_code` method returns the RFC 6750 `error` attribute for a `WWW-Authenticate`
+    /// challenge, or `None` when the request carried no credentials at all.
+    pub fn error_code(&self) -> Option<&'static str> {
+        match self {
+            Self::Unauthenticated => None,
+            Self::Expired | Self::InvalidToken => Some("invalid_token"),
+            Self::Forbidden => Some("insufficient_scope"),
+            Self::MfaRequired => Some("insufficient_user_authentication"),
+        }
+    }
+}
+
+impl std::fmt::Display for AuthReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let msg = match self {
+            Self::Unauthenticated => "credentials not provided",
+            Self::Expired => "credentials expired",
+            Self::Forbidden => "access forbidden",
+            Self::InvalidToken => "invalid token",
+            Self::MfaRequired => "multi-factor authentication required",
+        };
+        write!(f, "{msg}")
+    }
+}
+
+/// The `AuthError` struct holds the reason for an authorization failure, the optional realm and
+/// scope it applies to, and an optional underlying error.
+#[derive(Debug, thiserror::Error)]
+pub struct AuthError {
+    reason: AuthReason,
+    realm: Option<String>,
+    scope: Option<String>,
+    #[source]
+    source: Option<Box<dyn std::error::Error + Send + Sync>>,
+}
+
+impl AuthError {
+    /// The `new` method creates an `AuthError` for the given `reason`.
+    pub fn new(reason: AuthReason) -> Self {
+        Self {
+            reason,
+            realm: None,
+            scope: None,
+            source: None,
+        }
+    }
+
+    /// The `with_realm` method sets the protection realm of the resource.
+    pub fn with_realm<S: Into<String>>(mut self, realm: S) -> Self {
+        self.realm = Some(realm.into());
+        self
+    }
+
+    /// The `with_scope` method sets the scope required to access the resource.
+    pub fn with_scope<S: Into<String>>(mut self, scope: S) -> Self {
+        self.scope = Some(scope.into());
+        self
+    }
+
+    /// The `with_source` method attaches the underlying error, such as a token decoding failure.
+    pub fn with_source<E>(mut self, source: E) -> Self
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        self.source = Some(Box::new(source));
+        self
+    }
+
+    /// The `reason` method returns the [`AuthReason`] for the failure.
+    pub fn reason(&self) -> AuthReason {
+        self.reason
+    }
+
+    /// The `realm` method returns the protection realm, if set.
+    pub fn realm(&self) -> Option<&str> {
+        self.realm.as_deref()
+    }
+
+    /// The `scope` method returns the required scope, if set.
+    pub fn scope(&self) -> Option<&str> {
+        self.scope.as_deref()
+    }
+
+    /// The `www_authenticate` method returns the value of the `WWW-Authenticate` header for a
+    /// Bearer challenge, or `None` if the reason maps to 403 Forbidden.
+    ///
+    /// Quotes and backslashes in the realm and scope are escaped.  A realm or scope holding a
+    /// control character, such as a line break, cannot be sent in a header and returns an error.
+    pub fn www_authenticate(&self) -> Clean<Option<String>> {
+        if self.reason.status_code() != 401 {
+            return Ok(None);
+        }
+        let mut params = Vec::new();
+        if let Some(realm) = &self.realm {
+            params.push(format!("realm={}", quote("realm", realm)?));
+        }
+        if let Some(scope) = &self.scope {
+            params.push(format!("scope={}", quote("scope", scope)?));
+        }
+        if let Some(code) = self.reason.error_code() {
+            params.push(format!("error=\"{code}\""));
+            params.push(format!("error_description=\"{}\"", self.reason));
+        }
+        if params.is_empty() {
+            Ok(Some("Bearer".to_string()))
+        } else {
+            Ok(Some(format!("Bearer {}", params.join(", "))))
+        }
+    }
+}
+
+/// Writes `value` as an HTTP quoted string, escaping quotes and backslashes.
+fn quote(name: &str, value: &str) -> Clean<String> {
+    if value.chars().any(char::is_control) {
+        return Err(Bandage::Hint(format!(
+            "the {name} of a WWW-Authenticate challenge cannot hold control characters"
+        )));
+    }
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        if matches!(c, '"' | '\\') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    Ok(quoted)
+}
+
+impl std::fmt::Display for AuthError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.reason)?;
+        if let Some(realm) = &self.realm {
+            write!(f, " (realm: {realm})")?;
+        }
+        if let Some(scope) = &self.scope {
+            write!(f, " (scope: {scope})")?;
+        }
+        Ok(())
+    }
+}
+
+impl From<AuthReason> for AuthError {
+    fn from(reason: AuthReason) -> Self {
+        Self::new(reason)
+    }
+}
+
+#[cfg(feature = "route")]
+#[cfg_attr(docsrs, doc(cfg(feature = "route")))]
+impl axum::response::IntoResponse for AuthError {
+    fn into_response(self) -> axum::response::Response {
+        let status = axum::http::StatusCode::from_u16(self.reason.status_code())
+            .unwrap_or(axum::http::StatusCode::UNAUTHORIZED);
+        let challenge = match self.www_authenticate() {
+            Ok(challenge) => challenge,
+            Err(error) => return error.into_response(),
+        };
+        let mut response = (status, Bandage::Auth(self).user_message()).into_response();
+        if let Some(challenge) = challenge {
+            match axum::http::HeaderValue::from_str(&challenge) {
+                Ok(value) => {
+                    response
+                        .headers_mut()
+                        .insert(axum::http::header::WWW_AUTHENTICATE, value);
+                }
+                Err(e) => return Bandage::Hint(e.to_string()).into_response(),
+            }
+        }
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn challenge_lists_parameters() {
+        let error = AuthError::new(AuthReason::Expired)
+            .with_realm("api")
+            .with_scope("read write");
+        assert_eq!(
+            error.www_authenticate().unwrap().as_deref(),
+            Some(
+                r#"Bearer realm="api", scope="read write", error="invalid_token", error_description="credentials expired""#
+            )
+        );
+        assert_eq!(
+            AuthError::new(AuthReason::Unauthenticated)
+                .www_authenticate()
+                .unwrap()
+                .as_deref(),
+            Some("Bearer")
+        );
+        assert_eq!(
+            AuthError::new(AuthReason::Forbidden)
+                .www_authenticate()
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn challenge_escapes_quoted_strings() {
+        let error = AuthError::new(AuthReason::Unauthenticated).with_realm(r#"the "main" \ api"#);
+        assert_eq!(
+            error.www_authenticate().unwrap().as_deref(),
+            Some(r#"Bearer realm="the \"main\" \\ api""#)
+        );
+    }
+
+    #[test]
+    fn challenge_rejects_control_characters() {
+        let error = AuthError::new(AuthReason::Unauthenticated).with_scope("read\r\nX-Evil: 1");
+        assert!(matches!(error.www_authenticate(), Err(Bandage::Hint(_))));
+    }
+
+    #[cfg(feature = "route")]
+    #[test]
+    fn invalid_challenge_is_a_server_error() {
+        use axum::response::IntoResponse;
+        let response = AuthError::new(AuthReason::Expired)
+            .with_realm("api\n")
+            .into_response();
+        assert_eq!(response.status(), 500);
+        assert!(response
+            .headers()
+            .get(axum::http::header::WWW_AUTHENTICATE)
+            .is_none());
+        let response = AuthError::new(AuthReason::Expired)
+            .with_realm("api")
+            .into_response();
+        assert_eq!(response.status(), 401);
+        assert_eq!(
+            response.headers()[axum::http::header::WWW_AUTHENTICATE],
+            r#"Bearer realm="api", error="invalid_token", error_description="credentials expired""#
+        );
+    }
+
+    #[cfg(all(feature = "route", feature = "stream"))]
+    #[test]
+    fn response_body_is_the_user_message() {
+        use axum::response::IntoResponse;
+        let response = AuthError::new(AuthReason::Expired)
+            .with_realm("internal-api")
+            .into_response();
+        let bytes =
+            futures::executor::block_on(axum::body::to_bytes(response.into_body(), usize::MAX))
+                .unwrap();
+        let expected = Bandage::Auth(AuthError::new(AuthReason::Expired)).user_message();
+        assert_eq!(std::str::from_utf8(&bytes).unwrap(), expected);
+        assert!(!expected.contains("internal-api"));
+    }
+}
@@ -0,0 +1,199 @@
+//! The `collect` module gathers every error from an iterator or stream of results, instead of
+//! stopping at the first as `collect::<Result<Vec<_>, _>>()` does, so a validation pass can
+//! report all of its failures at once.
+//!
+//! ```
+//! use aid::prelude::*;
+//!
+//! fn validate(row: &&str) -> Clean<u32> {
+//!     Ok(row.parse()?)
+//! }
+//!
+//! let rows = ["101", "1o2", "103", "1O4"];
+//! let error = rows.iter().map(validate).take_errors(100).collect_all().unwrap_err();
+//! assert_eq!(error.kind(), BandageKind::Aggregate);
+//! ```
+use crate::prelude::{Bandage, Clean};
+
+/// The `AggregateError` struct holds every error collected from a batch.
+#[derive(Debug, thiserror::Error)]
+pub struct AggregateError {
+    errors: Vec<Bandage>,
+}
+
+impl AggregateError {
+    /// The `new` method wraps `errors`.
+    pub fn new(errors: Vec<Bandage>) -> Self {
+        Self { errors }
+    }
+
+    /// The `errors` method returns the errors, in the order they occurred.
+    pub fn errors(&self) -> &[Bandage] {
+        &self.errors
+    }
+
+    /// The `into_errors` method returns the errors.
+    pub fn into_errors(self) -> Vec<Bandage> {
+        self.errors
+    }
+}
+
+impl std::fmt::Display for AggregateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} error(s)", self.errors.len())?;
+        for error in &self.errors {
+            write!(f, "\n  - {error}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The `CleanIter` trait adds error-collecting methods to iterators of [`Clean`] results.
+pub trait CleanIter<T>: Iterator<Item = Clean<T>> + Sized {
+    /// The `collect_all` method returns every item, or a [`Bandage::Aggregate`] with every
+    /// error if any item failed.
+    fn collect_all(self) -> Clean<Vec<T>> {
+        let (items, errors) = self.partition_clean();
+        if errors.is_empty() {
+            Ok(items)
+        } else {
+            Err(AggregateError::new(errors).into())
+        }
+    }
+
+    /// The `partition_clean` method separates the items from the errors.
+    fn partition_clean(self) -> (Vec<T>, Vec<Bandage>) {
+        let mut items = Vec::new();
+        let mut errors = Vec::new();
+        for result in self {
+            match result {
+                Ok(item) => items.push(item),
+                Err(e) => errors.push(e),
+            }
+        }
+        (items, errors)
+    }
+
+    /// The `take_errors` method ends the iterator after it yields `n` errors.
+    fn take_errors(self, n: usize) -> TakeErrors<Self> {
+        TakeErrors {
+            iter: self,
+            remaining: n,
+        }
+    }
+}
+
+impl<T, I: Iterator<Item = Clean<T>>> CleanIter<T> for I {}
+
+/// The `TakeErrors` struct is an iterator that ends after a number of errors, returned by
+/// [`CleanIter::take_errors`].
+#[derive(Debug, Clone)]
+pub struct TakeErrors<I> {
+    iter: I,
+    remaining: usize,
+}
+
+impl<T, I: Iterator<Item = Clean<T>>> Iterator for TakeErrors<I> {
+    type Item = Clean<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let result = self.iter.next()?;
+        if result.is_err() {
+            self.remaining -= 1;
+        }
+        Some(result)
+    }
+}
+
+/// The `CleanStream` trait adds error-collecting methods to streams of [`Clean`] results.
+#[cfg(feature = "stream")]
+#[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+pub trait CleanStream<T: Send>: futures::Stream<Item = Clean<T>> + Send + Sized {
+    /// The `collect_all` method returns every item, or a [`Bandage::Aggregate`] with every
+    /// error if any item failed.
+    fn collect_all(self) -> impl std::future::Future<Output = Clean<Vec<T>>> + Send {
+        async move {
+            let (items, errors) = self.partition_clean().await;
+            if errors.is_empty() {
+                Ok(items)
+            } else {
+                Err(AggregateError::new(errors).into())
+            }
+        }
+    }
+
+    /// The `partition_clean` method separates the items from the errors.
+    fn partition_clean(self) -> impl std::future::Future<Output = (Vec<T>, Vec<Bandage>)> + Send {
+        use futures::StreamExt;
+        self.fold(
+            (Vec::new(), Vec::new()),
+            |(mut items, mut errors), result| async move {
+                match result {
+                    Ok(item) => items.push(item),
+                    Err(e) => errors.push(e),
+                }
+                (items, errors)
+            },
+        )
+    }
+
+    /// The `take_errors` method ends the stream after it yields `n` errors, without polling
+    /// the stream again.
+    fn take_errors(self, n: usize) -> impl futures::Stream<Item = Clean<T>> + Send {
+        use futures::StreamExt;
+        futures::stream::unfold((Box::pin(self), n), |(mut stream, remaining)| async move {
+            if remaining == 0 {
+                return None;
+            }
+            let result = stream.next().await?;
+            let remaining = remaining - usize::from(result.is_err());
+            Some((result, (stream, remaining)))
+        })
+    }
+}
+
+#[cfg(feature = "stream")]
+impl<T: Send, S: futures::Stream<Item = Clean<T>> + Send> CleanStream<T> for S {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows() -> Vec<Clean<u32>> {
+        ["1", "x", "3", "y", "5", "z"]
+            .iter()
+            .map(|row| Ok(row.parse()?))
+            .collect()
+    }
+
+    #[test]
+    fn collect_all_keeps_every_error() {
+        let error = rows().into_iter().collect_all().unwrap_err();
+        let Bandage::Aggregate(error) = error else {
+            panic!("expected an aggregate error, got {error:?}");
+        };
+        assert_eq!(error.errors().len(), 3);
+        assert!(error.to_string().starts_with("3 error(s)\n  - "));
+        let items = rows().into_iter().filter(Result::is_ok).collect_all();
+        assert_eq!(items.unwrap(), vec![1, 3, 5]);
+    }
+
+    #[test]
+    fn take_errors_stops_after_the_last_error() {
+        let (items, errors) = rows().into_iter().take_errors(2).partition_clean();
+        assert_eq!(items, vec![1, 3]);
+        assert_eq!(errors.len(), 2);
+        assert_eq!(rows().into_iter().take_errors(0).count(), 0);
+        assert_eq!(rows().into_iter().take_errors(10).count(), 6);
+    }
+
+    #[test]
+    fn take_errors_passes_items_through() {
+        let items: Vec<Clean<u32>> = vec![Ok(1), Ok(2)];
+        let taken: Vec<u32> = items.into_iter().take_errors(1).flatten().collect();
+        assert_eq!(taken, vec![1, 2]);
+    }
+}
@@ -0,0 +1,1258 @@
+//! The `config` module loads typed configuration from a `.env` file, the process environment and
+//! an optional TOML or JSON file, reporting every missing, malformed or unknown setting at once.
+//!
+//! Settings are layered with the config file lowest, then the `.env` file, then the process
+//! environment.  Environment variable names are matched against struct fields after removing the
+//! optional prefix and converting to lowercase, so `APP_DATABASE_URL` fills `database_url` when
+//! the prefix is `APP_`.
+//!
+//! Text settings, from the environment or a `.env` file, fill `bool` fields from `true`, `yes`,
+//! `on` or `1` and `false`, `no`, `off` or `0`, in any case.
+use crate::prelude::{Bandage, Clean};
+use serde::de::{self, DeserializeOwned, IntoDeserializer};
+use std::cell::Cell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+/// The `REDACTED` constant replaces secret values in configuration errors.
+const REDACTED: &str = "[redacted]";
+
+/// Key fragments that mark a setting as secret even when not registered with
+/// [`ConfigLoader::secret`].
+const SECRET_HINTS: [&str; 6] = [
+    "password",
+    "passwd",
+    "secret",
+    "token",
+    "api_key",
+    "private_key",
+];
+
+/// The `ConfigSource` enum records where a configuration value came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigSource {
+    /// The value came from the process environment.
+    Environment,
+    /// The value came from a file, at the given line if it could be located.
+    File {
+        /// The `path` field is the path of the file.
+        path: PathBuf,
+        /// The `line` field is the one-based line number of the setting.
+        line: Option<usize>,
+    },
+}
+
+impl std::fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Environment => write!(f, "environment"),
+            Self::File {
+                path,
+                line: Some(line),
+            } => write!(f, "{}:{line}", path.display()),
+            Self::File { path, line: None } => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// The `ConfigIssueKind` enum classifies a single configuration problem.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ConfigIssueKind {
+    /// A required setting was not provided by any source.
+    Missing,
+    /// A setting was provided but could not be deserialized into the field type.
+    Malformed,
+    /// A setting in a file does not correspond to any field.
+    Unknown,
+    /// A source file could not be read or parsed.
+    Unreadable,
+}
+
+/// The `ConfigIssue` struct describes one problem found while loading configuration.
+#[derive(Debug, Clone)]
+pub struct ConfigIssue {
+    kind: ConfigIssueKind,
+    key: String,
+    value: Option<String>,
+    source: Option<ConfigSource>,
+    message: Option<String>,
+}
+
+impl ConfigIssue {
+    /// The `kind` method returns the [`ConfigIssueKind`] of the issue.
+    pub fn kind(&self) -> ConfigIssueKind {
+        self.kind
+    }
+
+    /// The `key` method returns the name of the setting, or an empty string for file-level
+    /// issues.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// The `value` method returns the offending value, redacted if the setting is secret.
+    pub fn value(&self) -> Option<&str> {
+        self.value.as_deref()
+    }
+
+    /// The `source` method returns where the offending setting was found, if anywhere.
+    pub fn source(&self) -> Option<&ConfigSource> {
+        self.source.as_ref()
+    }
+
+    /// The `message` method returns the underlying error message, or for a secret setting only
+    /// the type expected, since the message may quote the value.
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+}
+
+impl std::fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.kind {
+            ConfigIssueKind::Missing => write!(f, "`{}` is missing", self.key)?,
+            ConfigIssueKind::Malformed => write!(f, "`{}` is malformed", self.key)?,
+            ConfigIssueKind::Unknown => write!(f, "`{}` is not a known setting", self.key)?,
+            ConfigIssueKind::Unreadable => write!(f, "could not read configuration")?,
+        }
+        if let Some(value) = &self.value {
+            write!(f, " (value {value:?})")?;
+        }
+        if let Some(source) = &self.source {
+            write!(f, " in {source}")?;
+        }
+        if let Some(message) = &self.message {
+            write!(f, ": {message}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The `ConfigError` struct holds every issue found while loading configuration.
+#[derive(Debug, Clone, thiserror::Error)]
+pub struct ConfigError {
+    issues: Vec<ConfigIssue>,
+}
+
+impl ConfigError {
+    /// The `issues` method returns the list of issues.
+    pub fn issues(&self) -> &[ConfigIssue] {
+        &self.issues
+    }
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} configuration issue(s)", self.issues.len())?;
+        for issue in &self.issues {
+            write!(f, "\n  - {issue}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The `ConfigLoader` struct is a builder for loading configuration into a user-defined type.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigLoader {
+    dotenv: Option<PathBuf>,
+    skip_dotenv: bool,
+    file: Option<PathBuf>,
+    prefix: Option<String>,
+    secrets: BTreeSet<String>,
+}
+
+impl ConfigLoader {
+    /// The `new` method creates a loader that searches the current directory and its parents for
+    /// a `.env` file.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The `dotenv` method sets the path of the `.env` file instead of searching for one.
+    pub fn dotenv<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.dotenv = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// The `skip_dotenv` method disables loading a `.env` file.
+    pub fn skip_dotenv(mut self) -> Self {
+        self.skip_dotenv = true;
+        self
+    }
+
+    /// The `file` method sets a TOML or JSON file to read settings from, selected by extension.
+    pub fn file<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.file = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// The `prefix` method restricts environment variables and `.env` settings to those starting
+    /// with `prefix`, which is removed before matching field names.
+    ///
+    /// With a prefix, a `.env` setting that matches no field is reported as unknown.  Without
+    /// one, the `.env` file is read like the environment, and a type deserialized as a map, such
+    /// as one with a `#[serde(flatten)]` field, receives every variable.
+    pub fn prefix<S: Into<String>>(mut self, prefix: S) -> Self {
+        self.prefix = Some(prefix.into());
+        self
+    }
+
+    /// The `secret` method marks the setting `key` as secret, so its value is redacted from
+    /// errors.  Keys containing `password`, `secret`, `token`, `api_key` or `private_key` are
+    /// secret by default.
+    pub fn secret<S: AsRef<str>>(mut self, key: S) -> Self {
+        self.secrets.insert(key.as_ref().to_lowercase());
+        self
+    }
+
+    /// The `load` method reads every source and deserializes the result into `T`, returning a
+    /// [`Bandage::Config`] listing every issue found if any source or setting is invalid.
+    pub fn load<T: DeserializeOwned>(&self) -> Clean<T> {
+        let mut issues = Vec::new();
+        let entries = self.gather(&mut issues);
+
+        let mut bad = BTreeSet::new();
+        let mut unknown = BTreeSet::new();
+        let mut missing = Vec::new();
+        let mut fields = None;
+        let mut result = None;
+        // Each failed pass records a new bad, unknown or missing key, or stops, so the loop ends
+        // once every key and field has been checked.
+        loop {
+            let mut pending = entries
+                .iter()
+                .filter(|(key, _)| !unknown.contains(*key))
+                .map(|(key, entry)| Pending {
+                    key: key.as_str(),
+                    value: match &entry.value {
+                        _ if bad.contains(key) => PendingValue::Placeholder,
+                        Value::Text(text) => PendingValue::Text(text),
+                        Value::Json(json) => PendingValue::Json(json),
+                    },
+                    strict: entry.strict,
+                    secret: self.is_secret(key),
+                })
+                .collect::<Vec<Pending>>();
+            pending.extend(missing.iter().map(|key: &String| Pending {
+                key: key.as_str(),
+                value: PendingValue::Placeholder,
+                strict: true,
+                secret: false,
+            }));
+
+            let seen = Cell::new(None);
+            let root = Root {
+                entries: &pending,
+                fields: &seen,
+            };
+            let outcome = T::deserialize(root);
+            fields = seen.get().or(fields);
+            match outcome {
+                Ok(value) => {
+                    result = Some(value);
+                    break;
+                }
+                Err(DeError::Missing(key)) if !missing.contains(&key) => {
+                    issues.push(ConfigIssue {
+                        kind: ConfigIssueKind::Missing,
+                        key: key.clone(),
+                        value: None,
+                        source: None,
+                        message: None,
+                    });
+                    missing.push(key);
+                }
+                Err(DeError::Value { key, message }) if !bad.contains(&key) => {
+                    let entry = &entries[&key];
+                    let value = match &entry.value {
+                        _ if self.is_secret(&key) => REDACTED.to_string(),
+                        Value::Text(text) => text.clone(),
+                        Value::Json(json) => json.to_string(),
+                    };
+                    issues.push(ConfigIssue {
+                        kind: ConfigIssueKind::Malformed,
+                        key: key.clone(),
+                        value: Some(value),
+                        source: Some(entry.source.clone()),
+                        message,
+                    });
+                    bad.insert(key);
+                }
+                Err(DeError::Key { key }) if !unknown.contains(&key) => {
+                    issues.push(ConfigIssue {
+                        kind: ConfigIssueKind::Unknown,
+                        key: key.clone(),
+                        value: None,
+                        source: entries.get(&key).map(|e| e.source.clone()),
+                        message: None,
+                    });
+                    unknown.insert(key);
+                }
+                Err(error) => {
+                    // Placeholder values could not stand in for a bad setting, so the remaining
+                    // fields cannot be checked; report what has been found so far.
+                    if issues.is_empty() {
+                        issues.push(ConfigIssue {
+                            kind: ConfigIssueKind::Malformed,
+                            key: String::new(),
+                            value: None,
+                            source: None,
+                            message: Some(error.to_string()),
+                        });
+                    }
+                    break;
+                }
+            }
+        }
+
+        if let Some(fields) = fields {
+            for (key, entry) in &entries {
+                let reported = issues.iter().any(|i| &i.key == key);
+                if entry.strict && !fields.contains(&key.as_str()) && !reported {
+                    issues.push(ConfigIssue {
+                        kind: ConfigIssueKind::Unknown,
+                        key: key.clone(),
+                        value: None,
+                        source: Some(entry.source.clone()),
+                        message: None,
+                    });
+                }
+            }
+        }
+
+        match result {
+            Some(value) if issues.is_empty() => Ok(value),
+            _ => Err(Bandage::Config(ConfigError { issues })),
+        }
+    }
+
+    fn is_secret(&self, key: &str) -> bool {
+        let key = key.to_lowercase();
+        self.secrets.contains(&key) || SECRET_HINTS.iter().any(|hint| key.contains(hint))
+    }
+
+    /// Normalizes an environment variable name into a field name, or returns `None` if it lacks
+    /// the configured prefix.
+    fn env_key(&self, name: &str) -> Option<String> {
+        match &self.prefix {
+            Some(prefix) => name.strip_prefix(prefix.as_str()).map(str::to_lowercase),
+            None => Some(name.to_lowercase()),
+        }
+    }
+
+    /// Reads every source into one map, later sources overriding earlier ones.
+    fn gather(&self, issues: &mut Vec<ConfigIssue>) -> BTreeMap<String, Entry> {
+        let mut entries = BTreeMap::new();
+        if let Some(path) = &self.file {
+            self.read_file(path, &mut entries, issues);
+        }
+        if !self.skip_dotenv {
+            let path = match &self.dotenv {
+                Some(path) => Some(path.clone()),
+                None => find_dotenv(),
+            };
+            if let Some(path) = path {
+                self.read_dotenv(&path, &mut entries, issues);
+            }
+        }
+        for (name, value) in std::env::vars_os() {
+            let (Some(name), Some(value)) = (name.to_str(), value.to_str()) else {
+                continue;
+            };
+            if let Some(key) = self.env_key(name) {
+                entries.insert(
+                    key,
+                    Entry {
+                        value: Value::Text(value.to_string()),
+                        source: ConfigSource::Environment,
+                        strict: false,
+                    },
+                );
+            }
+        }
+        entries
+    }
+
+    fn read_dotenv(
+        &self,
+        path: &Path,
+        entries: &mut BTreeMap<String, Entry>,
+        issues: &mut Vec<ConfigIssue>,
+    ) {
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) => {
+                issues.push(unreadable(path, None, e.to_string()));
+                return;
+            }
+        };
+        let iter = match dotenvy::from_path_iter(path) {
+            Ok(iter) => iter,
+            Err(e) => {
+                issues.push(unreadable(path, None, e.to_string()));
+                return;
+            }
+        };
+        for item in iter {
+            match item {
+                Ok((name, value)) => {
+                    if let Some(key) = self.env_key(&name) {
+                        entries.insert(
+                            key,
+                            Entry {
+                                value: Value::Text(value),
+                                source: ConfigSource::File {
+                                    path: path.to_path_buf(),
+                                    line: find_line(&text, &name, false),
+                                },
+                                strict: self.prefix.is_some(),
+                            },
+                        );
+                    }
+                }
+                Err(dotenvy::Error::LineParse(line, index)) => {
+                    let number = text.lines().position(|l| l.contains(&line)).map(|i| i + 1);
+                    let message = format!("invalid syntax at position {index}");
+                    issues.push(unreadable(path, number, message));
+                }
+                Err(e) => {
+                    issues.push(unreadable(path, None, e.to_string()));
+                    break;
+                }
+            }
+        }
+    }
+
+    fn read_file(
+        &self,
+        path: &Path,
+        entries: &mut BTreeMap<String, Entry>,
+        issues: &mut Vec<ConfigIssue>,
+    ) {
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) => {
+                issues.push(unreadable(path, None, e.to_string()));
+                return;
+            }
+        };
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or_default();
+        let table = match extension.to_lowercase().as_str() {
+            "toml" => match toml::from_str::<toml::Table>(&text) {
+                Ok(table) => table
+                    .into_iter()
+                    .map(|(k, v)| (k, serde_json::to_value(v).unwrap_or_default()))
+                    .collect::<Vec<(String, serde_json::Value)>>(),
+                Err(e) => {
+                    let line = e.span().map(|s| text[..s.start].matches('\n').count() + 1);
+                    issues.push(unreadable(path, line, e.message().to_string()));
+                    return;
+                }
+            },
+            "json" => {
+                match serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(&text) {
+                    Ok(map) => map.into_iter().collect(),
+                    Err(e) => {
+                        issues.push(unreadable(path, Some(e.line()), e.to_string()));
+                        return;
+                    }
+                }
+            }
+            _ => {
+                let message = "expected a .toml or .json file".to_string();
+                issues.push(unreadable(path, None, message));
+                return;
+            }
+        };
+        for (key, value) in table {
+            let line = find_line(&text, &key, true);
+            entries.insert(
+                key,
+                Entry {
+                    value: Value::Json(value),
+                    source: ConfigSource::File {
+                        path: path.to_path_buf(),
+                        line,
+                    },
+                    strict: true,
+                },
+            );
+        }
+    }
+}
+
+fn unreadable(path: &Path, line: Option<usize>, message: String) -> ConfigIssue {
+    ConfigIssue {
+        kind: ConfigIssueKind::Unreadable,
+        key: String::new(),
+        value: None,
+        source: Some(ConfigSource::File {
+            path: path.to_path_buf(),
+            line,
+        }),
+        message: Some(message),
+    }
+}
+
+/// Searches the current directory and its parents for a `.env` file.
+fn find_dotenv() -> Option<PathBuf> {
+    let dir = std::env::current_dir().ok()?;
+    dir.ancestors()
+        .map(|d| d.join(".env"))
+        .find(|path| path.is_file())
+}
+
+/// Returns the one-based line on which the top-level `key` is assigned in a `.env`, TOML or
+/// JSON file, or on which its TOML table starts.
+///
+/// With `nested` set, for TOML and JSON files, keys inside a TOML table or a nested JSON object
+/// are skipped, so a top-level `port` is not found on the `port` line of a `[database]` table.
+fn find_line(text: &str, key: &str, nested: bool) -> Option<usize> {
+    let assigns = |line: &str| {
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let line = line.strip_prefix('"').unwrap_or(line);
+        line.strip_prefix(key).is_some_and(|rest| {
+            let rest = rest.strip_prefix('"').unwrap_or(rest).trim_start();
+            rest.starts_with('=') || rest.starts_with(':')
+        })
+    };
+    if !nested {
+        return text
+            .lines()
+            .position(|line| assigns(line.trim_start()))
+            .map(|i| i + 1);
+    }
+    // The depth of JSON objects and multi-line arrays at the start of each line.
+    let mut depth = 0usize;
+    let mut in_table = false;
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim_start();
+        if depth == 0 && line.starts_with('[') {
+            // A TOML table header: `[key]`, `[key.sub]` or `[[key]]`.
+            let name = line.trim_start_matches('[').trim_start();
+            let name = name.strip_prefix('"').unwrap_or(name);
+            let found = name.strip_prefix(key).is_some_and(|rest| {
+                let rest = rest.strip_prefix('"').unwrap_or(rest).trim_start();
+                rest.starts_with(']') || rest.starts_with('.')
+            });
+            if found {
+                return Some(i + 1);
+            }
+            in_table = true;
+            continue;
+        }
+        if !in_table && depth <= 1 && assigns(line) {
+            return Some(i + 1);
+        }
+        depth = nesting(line, depth);
+    }
+    None
+}
+
+/// Returns `depth` after the brackets and braces of `line`, skipping strings and comments.
+fn nesting(line: &str, mut depth: usize) -> usize {
+    let mut quote = None;
+    let mut escaped = false;
+    for c in line.chars() {
+        match quote {
+            Some(q) => {
+                if escaped {
+                    escaped = false;
+                } else if c == '\\' && q == '"' {
+                    escaped = true;
+                } else if c == q {
+                    quote = None;
+                }
+            }
+            None => match c {
+                '"' | '\'' => quote = Some(c),
+                '#' => break,
+                '{' | '[' => depth += 1,
+                '}' | ']' => depth = depth.saturating_sub(1),
+                _ => {}
+            },
+        }
+    }
+    depth
+}
+
+#[derive(Debug)]
+struct Entry {
+    value: Value,
+    source: ConfigSource,
+    /// Whether the setting is reported as unknown if it matches no field.
+    strict: bool,
+}
+
+#[derive(Debug)]
+enum Value {
+    Text(String),
+    Json(serde_json::Value),
+}
+
+struct Pending<'a> {
+    key: &'a str,
+    value: PendingValue<'a>,
+    strict: bool,
+    /// Whether errors must not describe the value.
+    secret: bool,
+}
+
+enum PendingValue<'a> {
+    Text(&'a str),
+    Json(&'a serde_json::Value),
+    /// Stands in for a missing or malformed setting so later settings can still be checked.
+    Placeholder,
+}
+
+/// The deserialization error used while probing settings, which remembers the failing key.
+#[derive(Debug)]
+enum DeError {
+    Missing(String),
+    Value {
+        key: String,
+        message: Option<String>,
+    },
+    /// A value of the wrong type or form, kept apart so a secret value can be left out.
+    Invalid {
+        expected: String,
+        found: String,
+    },
+    Key {
+        key: String,
+    },
+    Placeholder,
+    Custom(String),
+}
+
+impl std::fmt::Display for DeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Missing(key) => write!(f, "missing field `{key}`"),
+            Self::Value {
+                key,
+                message: Some(message),
+            } => write!(f, "{key}: {message}"),
+            Self::Value { key, message: None } => write!(f, "{key} is malformed"),
+            Self::Invalid { expected, found } => write!(f, "expected {expected}, found {found}"),
+            Self::Key { key } => write!(f, "unknown field `{key}`"),
+            Self::Placeholder => write!(f, "could not check remaining settings"),
+            Self::Custom(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for DeError {}
+
+impl de::Error for DeError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Self::Custom(msg.to_string())
+    }
+
+    fn missing_field(field: &'static str) -> Self {
+        Self::Missing(field.to_string())
+    }
+
+    fn invalid_type(unexp: de::Unexpected<'_>, exp: &dyn de::Expected) -> Self {
+        Self::Invalid {
+            expected: exp.to_string(),
+            found: unexp.to_string(),
+        }
+    }
+
+    fn invalid_value(unexp: de::Unexpected<'_>, exp: &dyn de::Expected) -> Self {
+        Self::Invalid {
+            expected: exp.to_string(),
+            found: unexp.to_string(),
+        }
+    }
+}
+
+struct Root<'a> {
+    entries: &'a [Pending<'a>],
+    fields: &'a Cell<Option<&'static [&'static str]>>,
+}
+
+impl<'de> de::Deserializer<'de> for Root<'_> {
+    type Error = DeError;
+
+    fn deserialize_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        // Without a field list every setting is passed on, for maps and flattened fields.
+        let entries = self.entries.iter().collect();
+        visitor.visit_map(RootMap { entries, index: 0 })
+    }
+
+    fn deserialize_struct<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.fields.set(Some(fields));
+        let entries = self
+            .entries
+            .iter()
+            .filter(|p| p.strict || fields.contains(&p.key))
+            .collect();
+        visitor.visit_map(RootMap { entries, index: 0 })
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+struct RootMap<'a> {
+    entries: Vec<&'a Pending<'a>>,
+    index: usize,
+}
+
+impl<'de> de::MapAccess<'de> for RootMap<'_> {
+    type Error = DeError;
+
+    fn next_key_seed<K: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        let Some(pending) = self.entries.get(self.index) else {
+            return Ok(None);
+        };
+        let key: de::value::StrDeserializer<DeError> = pending.key.into_deserializer();
+        seed.deserialize(key).map(Some).map_err(|_| DeError::Key {
+            key: pending.key.to_string(),
+        })
+    }
+
+    fn next_value_seed<V: de::DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        let pending = self.entries[self.index];
+        self.index += 1;
+        let wrap = |message: Option<String>| DeError::Value {
+            key: pending.key.to_string(),
+            message,
+        };
+        match pending.value {
+            // Only the expected type is reported for a secret, since the other messages quote or
+            // escape the value.
+            PendingValue::Text(text) => seed.deserialize(Text(text)).map_err(|e| match e {
+                DeError::Invalid { expected, .. } if pending.secret => {
+                    wrap(Some(format!("expected {expected}")))
+                }
+                _ if pending.secret => wrap(None),
+                e => wrap(Some(e.to_string())),
+            }),
+            PendingValue::Json(json) => seed
+                .deserialize(json.clone())
+                .map_err(|e| wrap((!pending.secret).then(|| e.to_string()))),
+            PendingValue::Placeholder => seed
+                .deserialize(Placeholder)
+                .map_err(|_| DeError::Placeholder),
+        }
+    }
+}
+
+/// Deserializes a string from the environment, parsing it according to the requested type.
+#[derive(Copy, Clone)]
+struct Text<'a>(&'a str);
+
+impl Text<'_> {
+    fn parse<T>(&self, expected: &str) -> Result<T, DeError>
+    where
+        T: std::str::FromStr,
+        T::Err: std::fmt::Display,
+    {
+        self.0.trim().parse::<T>().map_err(|e| DeError::Invalid {
+            expected: expected.to_string(),
+            found: format!("{:?}: {e}", self.0),
+        })
+    }
+}
+
+impl<'de> IntoDeserializer<'de, DeError> for Text<'_> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident : $ty:ty),* $(,)?) => {
+        $(
+            fn $method<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                visitor.$visit(self.parse::<$ty>(stringify!($ty))?)
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for Text<'_> {
+    type Error = DeError;
+
+    fn deserialize_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_str(self.0)
+    }
+
+    fn deserialize_bool<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.0.trim().to_lowercase().as_str() {
+            "true" | "yes" | "on" | "1" => visitor.visit_bool(true),
+            "false" | "no" | "off" | "0" => visitor.visit_bool(false),
+            _ => Err(DeError::Invalid {
+                expected: "bool".to_string(),
+                found: format!(
+                    "{:?}: expected true, false, yes, no, on, off, 1 or 0",
+                    self.0
+                ),
+            }),
+        }
+    }
+
+    deserialize_parsed! {
+        deserialize_i8 => visit_i8: i8,
+        deserialize_i16 => visit_i16: i16,
+        deserialize_i32 => visit_i32: i32,
+        deserialize_i64 => visit_i64: i64,
+        deserialize_i128 => visit_i128: i128,
+        deserialize_u8 => visit_u8: u8,
+        deserialize_u16 => visit_u16: u16,
+        deserialize_u32 => visit_u32: u32,
+        deserialize_u64 => visit_u64: u64,
+        deserialize_u128 => visit_u128: u128,
+        deserialize_f32 => visit_f32: f32,
+        deserialize_f64 => visit_f64: f64,
+        deserialize_char => visit_char: char,
+    }
+
+    fn deserialize_option<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        if self.0.is_empty() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_newtype_struct<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let items = self
+            .0
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(Text);
+        visitor.visit_seq(de::value::SeqDeserializer::new(items))
+    }
+
+    fn deserialize_enum<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let variant: de::value::StrDeserializer<DeError> = self.0.trim().into_deserializer();
+        visitor.visit_enum(variant)
+    }
+
+    serde::forward_to_deserialize_any! {
+        str string bytes byte_buf unit unit_struct tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+/// Produces an empty value of whatever type is requested, standing in for a bad setting.
+struct Placeholder;
+
+impl<'de> de::Deserializer<'de> for Placeholder {
+    type Error = DeError;
+
+    fn deserialize_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_bool<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_bool(false)
+    }
+
+    fn deserialize_i64<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_i64(0)
+    }
+
+    fn deserialize_u64<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_u64(0)
+    }
+
+    fn deserialize_f64<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_f64(0.0)
+    }
+
+    fn deserialize_str<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_str("")
+    }
+
+    fn deserialize_option<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_none()
+    }
+
+    fn deserialize_newtype_struct<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_seq(de::value::SeqDeserializer::new(std::iter::empty::<()>()))
+    }
+
+    fn deserialize_map<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_map(de::value::MapDeserializer::new(
+            std::iter::empty::<((), ())>(),
+        ))
+    }
+
+    fn deserialize_enum<V: de::Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        Err(DeError::Placeholder)
+    }
+
+    serde::forward_to_deserialize_any! {
+        unit unit_struct tuple tuple_struct struct identifier ignored_any bytes byte_buf
+    }
+
+    fn deserialize_i8<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_i8(0)
+    }
+
+    fn deserialize_i16<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_i16(0)
+    }
+
+    fn deserialize_i32<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_i32(0)
+    }
+
+    fn deserialize_u8<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_u8(0)
+    }
+
+    fn deserialize_u16<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_u16(0)
+    }
+
+    fn deserialize_u32<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_u32(0)
+    }
+
+    fn deserialize_f32<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_f32(0.0)
+    }
+
+    fn deserialize_char<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_char(' ')
+    }
+
+    fn deserialize_string<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_str("")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Mutex, MutexGuard, PoisonError};
+
+    #[derive(Debug, serde::Deserialize)]
+    struct Settings {
+        port: u16,
+        name: String,
+        #[serde(default)]
+        debug: bool,
+    }
+
+    /// Serializes the tests, which all read the process environment and some change it.
+    static ENV: Mutex<()> = Mutex::new(());
+
+    /// The directory and environment variables of one test, removed when dropped.
+    struct Scratch {
+        dir: PathBuf,
+        vars: Vec<&'static str>,
+        _env: MutexGuard<'static, ()>,
+    }
+
+    impl Scratch {
+        fn new(test: &str) -> Self {
+            let env = ENV.lock().unwrap_or_else(PoisonError::into_inner);
+            let dir =
+                std::env::temp_dir().join(format!("aid-config-{test}-{}", std::process::id()));
+            std::fs::create_dir_all(&dir).unwrap();
+            Self {
+                dir,
+                vars: Vec::new(),
+                _env: env,
+            }
+        }
+
+        /// Writes `text` to a file named `name` in the test's directory.
+        fn write(&self, name: &str, text: &str) -> PathBuf {
+            let path = self.dir.join(name);
+            std::fs::write(&path, text).unwrap();
+            path
+        }
+
+        /// Sets the environment variable `name` until the test ends.
+        fn set(&mut self, name: &'static str, value: &str) {
+            std::env::set_var(name, value);
+            self.vars.push(name);
+        }
+    }
+
+    impl Drop for Scratch {
+        fn drop(&mut self) {
+            for name in &self.vars {
+                std::env::remove_var(name);
+            }
+            let _ = std::fs::remove_dir_all(&self.dir);
+        }
+    }
+
+    fn issues(result: Clean<Settings>) -> Vec<ConfigIssue> {
+        match result {
+            Err(Bandage::Config(e)) => e.issues().to_vec(),
+            other => panic!("expected a config error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn loads_layered_settings() {
+        let mut scratch = Scratch::new("layered");
+        let file = scratch.write("settings.toml", "port = 80\nname = \"parcels\"\n");
+        let dotenv = scratch.write(".env", "AIDTEST_LAYERED_PORT=8080\nRUST_LOG=debug\n");
+        scratch.set("AIDTEST_LAYERED_DEBUG", "true");
+        let settings: Settings = ConfigLoader::new()
+            .file(&file)
+            .dotenv(&dotenv)
+            .prefix("AIDTEST_LAYERED_")
+            .load()
+            .unwrap();
+        assert_eq!(settings.port, 8080);
+        assert_eq!(settings.name, "parcels");
+        assert!(settings.debug);
+    }
+
+    #[test]
+    fn reports_every_issue() {
+        let json = "{\n  \"port\": \"high\",\n  \"color\": \"red\"\n}\n";
+        let scratch = Scratch::new("every");
+        let file = scratch.write("settings.json", json);
+        let issues = issues(
+            ConfigLoader::new()
+                .file(&file)
+                .skip_dotenv()
+                .prefix("AIDTEST_EVERY_")
+                .load(),
+        );
+        let kinds: Vec<(ConfigIssueKind, &str)> =
+            issues.iter().map(|i| (i.kind(), i.key())).collect();
+        assert!(kinds.contains(&(ConfigIssueKind::Malformed, "port")));
+        assert!(kinds.contains(&(ConfigIssueKind::Missing, "name")));
+        assert!(kinds.contains(&(ConfigIssueKind::Unknown, "color")));
+        let port = issues.iter().find(|i| i.key() == "port").unwrap();
+        assert_eq!(
+            port.source(),
+            Some(&ConfigSource::File {
+                path: file,
+                line: Some(2)
+            })
+        );
+    }
+
+    #[test]
+    fn secrets_stay_out_of_messages() {
+        #[derive(Debug, serde::Deserialize)]
+        #[allow(dead_code)]
+        struct Secrets {
+            api_token: u32,
+        }
+        let mut scratch = Scratch::new("secret");
+        for secret in ["hunter\"2", r"back\slash", "contraseña"] {
+            scratch.set("AIDTEST_SECRET_API_TOKEN", secret);
+            let error = ConfigLoader::new()
+                .skip_dotenv()
+                .prefix("AIDTEST_SECRET_")
+                .load::<Secrets>()
+                .unwrap_err();
+            let Bandage::Config(config) = &error else {
+                panic!("expected a config error, got {error:?}");
+            };
+            let text = config.to_string();
+            assert!(!text.contains("hunter"), "{text}");
+            assert!(!text.contains("slash"), "{text}");
+            assert!(!text.contains("contrase"), "{text}");
+            assert_eq!(config.issues()[0].message(), Some("expected u32"));
+        }
+    }
+
+    #[test]
+    fn toml_error_line() {
+        let scratch = Scratch::new("toml");
+        let file = scratch.write("settings.toml", "port = 80\n= 2\n");
+        let issues = issues(ConfigLoader::new().file(&file).skip_dotenv().load());
+        assert_eq!(issues[0].kind(), ConfigIssueKind::Unreadable);
+        assert_eq!(
+            issues[0].source(),
+            Some(&ConfigSource::File {
+                path: file,
+                line: Some(2)
+            })
+        );
+    }
+
+    #[test]
+    fn dotenv_keys_without_prefix_are_ignored() {
+        let scratch = Scratch::new("unprefixed");
+        let dotenv = scratch.write(
+            ".env",
+            "AIDTEST_UNPREFIXED_PORT=1\nAIDTEST_UNPREFIXED_COLOR=red\nRUST_LOG=debug\n",
+        );
+        let issues = issues(
+            ConfigLoader::new()
+                .dotenv(&dotenv)
+                .prefix("AIDTEST_UNPREFIXED_")
+                .load(),
+        );
+        assert!(issues
+            .iter()
+            .any(|i| i.kind() == ConfigIssueKind::Unknown && i.key() == "color"));
+        assert!(!issues.iter().any(|i| i.key().contains("rust_log")));
+
+        let dotenv = scratch.write("plain.env", "PORT=1\nNAME=a\nRUST_LOG=debug\n");
+        let settings: Settings = ConfigLoader::new().dotenv(&dotenv).load().unwrap();
+        assert_eq!((settings.port, settings.name.as_str()), (1, "a"));
+    }
+
+    #[test]
+    fn unreadable_dotenv_is_reported() {
+        let scratch = Scratch::new("unreadable");
+        let dir = scratch.dir.join("dir.env");
+        std::fs::create_dir_all(&dir).unwrap();
+        let issues = issues(ConfigLoader::new().dotenv(&dir).load());
+        assert_eq!(issues[0].kind(), ConfigIssueKind::Unreadable);
+    }
+
+    #[test]
+    fn flattened_fields_read_the_environment() {
+        #[derive(Debug, serde::Deserialize)]
+        struct Outer {
+            #[serde(flatten)]
+            extra: std::collections::HashMap<String, String>,
+        }
+        let mut scratch = Scratch::new("flatten");
+        scratch.set("AIDTEST_FLATTEN_ZONE", "west");
+        let outer: Outer = ConfigLoader::new().skip_dotenv().load().unwrap();
+        assert_eq!(
+            outer.extra.get("aidtest_flatten_zone").map(String::as_str),
+            Some("west")
+        );
+    }
+
+    #[test]
+    fn lines_skip_nested_tables() {
+        let toml = "name = \"a\"\n\n[database]\nport = 5432\n\n[[zones]]\nname = \"west\"\n";
+        assert_eq!(find_line(toml, "name", true), Some(1));
+        assert_eq!(find_line(toml, "database", true), Some(3));
+        assert_eq!(find_line(toml, "zones", true), Some(6));
+        assert_eq!(find_line(toml, "port", true), None);
+        let json =
+            "{\n  \"db\": {\n    \"port\": 1,\n    \"note\": \"a { b\"\n  },\n  \"port\": 2\n}\n";
+        assert_eq!(find_line(json, "db", true), Some(2));
+        assert_eq!(find_line(json, "port", true), Some(6));
+        assert_eq!(find_line("export PORT=1\n", "PORT", false), Some(1));
+    }
+
+    #[test]
+    fn table_issues_point_at_the_header() {
+        #[derive(Debug, serde::Deserialize)]
+        #[allow(dead_code)]
+        struct Nested {
+            database: u16,
+        }
+        let scratch = Scratch::new("nested");
+        let file = scratch.write("settings.toml", "[database]\ndatabase = 1\n");
+        let result = ConfigLoader::new()
+            .file(&file)
+            .skip_dotenv()
+            .load::<Nested>();
+        let Err(Bandage::Config(error)) = result else {
+            panic!("expected a config error, got {result:?}");
+        };
+        assert_eq!(
+            error.issues()[0].source(),
+            Some(&ConfigSource::File {
+                path: file,
+                line: Some(1)
+            })
+        );
+    }
+
+    #[test]
+    fn bools_accept_common_words() {
+        #[derive(Debug, serde::Deserialize)]
+        struct Flags {
+            a: bool,
+            b: bool,
+            c: bool,
+            d: bool,
+        }
+        let mut scratch = Scratch::new("bools");
+        for (name, value) in [
+            ("AIDTEST_BOOLS_A", "Yes"),
+            ("AIDTEST_BOOLS_B", "0"),
+            ("AIDTEST_BOOLS_C", " on "),
+            ("AIDTEST_BOOLS_D", "FALSE"),
+        ] {
+            scratch.set(name, value);
+        }
+        let flags: Flags = ConfigLoader::new()
+            .skip_dotenv()
+            .prefix("AIDTEST_BOOLS_")
+            .load()
+            .unwrap();
+        assert_eq!(
+            (flags.a, flags.b, flags.c, flags.d),
+            (true, false, true, false)
+        );
+        scratch.set("AIDTEST_BOOLS_D", "maybe");
+        let error = ConfigLoader::new()
+            .skip_dotenv()
+            .prefix("AIDTEST_BOOLS_")
+            .load::<Flags>()
+            .unwrap_err();
+        let Bandage::Config(error) = error else {
+            panic!("expected a config error, got {error:?}");
+        };
+        assert_eq!(error.issues()[0].key(), "d");
+        let message = error.issues()[0].message().unwrap();
+        assert!(
+            message.starts_with("expected bool, found \"maybe\""),
+            "{message}"
+        );
+    }
+}
@@ -0,0 +1,309 @@
+//! The `crash` module writes JSON crash reports for fatal errors, so an application can offer to
+//! send them the next time it starts.
+use crate::prelude::{Bandage, Clean};
+use crate::redact::scrub;
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+/// Recent log lines kept for crash reports, filled by [`record_log`].
+static RECENT_LOGS: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+/// The number of reports written by this process, so two reports in the same instant get
+/// different file names.
+static WRITTEN: AtomicU64 = AtomicU64::new(0);
+
+/// The number of log lines kept for crash reports by default.
+pub const DEFAULT_LOG_CAPACITY: usize = 200;
+
+/// The `CrashReport` struct is the contents of a crash report file.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct CrashReport {
+    /// The `app` field is the name of the application.
+    pub app: String,
+    /// The `app_version` field is the version of the application.
+    pub app_version: String,
+    /// The `aid_version` field is the version of this crate.
+    pub aid_version: String,
+    /// The `features` field lists the enabled features of this crate.
+    pub features: Vec<String>,
+    /// The `os` field is the operating system, such as `windows`.
+    pub os: String,
+    /// The `arch` field is the CPU architecture, such as `x86_64`.
+    pub arch: String,
+    /// The `timestamp` field is the time of the crash in seconds since the Unix epoch.
+    pub timestamp: u64,
+    /// The `name` field is the name of the [`Bandage`] variant.
+    pub name: String,
+    /// The `code` field is the [`Bandage::code`] of the error.
+    pub code: String,
+    /// The `chain` field is the message of the error and each error in its source chain.
+    pub chain: Vec<String>,
+    /// The `backtrace` field is the backtrace of the panic, or of the call that wrote the report.
+    pub backtrace: Option<String>,
+    /// The `logs` field holds the most recent log lines before the crash.
+    pub logs: Vec<String>,
+}
+
+/// The `CrashReporter` struct writes, lists, reads and deletes crash reports for an application.
+#[derive(Debug, Clone)]
+pub struct CrashReporter {
+    app: String,
+    version: String,
+    dir: PathBuf,
+}
+
+impl CrashReporter {
+    /// The `new` method creates a reporter that keeps reports in the `crash` folder of the
+    /// application's platform data directory.
+    pub fn new<S: Into<String>, V: Into<String>>(app: S, version: V) -> Self {
+        let app = app.into();
+        let dir = data_dir().join(&app).join("crash");
+        Self {
+            app,
+            version: version.into(),
+            dir,
+        }
+    }
+
+    /// The `dir` method sets the directory that holds the reports.
+    pub fn dir<P: AsRef<Path>>(mut self, dir: P) -> Self {
+        self.dir = dir.as_ref().to_path_buf();
+        self
+    }
+
+    /// The `path` method returns the directory that holds the reports.
+    pub fn path(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The `report` method builds a [`CrashReport`] for `error` without writing it.
+    pub fn report(&self, error: &Bandage) -> CrashReport {
+        let backtrace = match error {
+            Bandage::Panic(panic) => panic.backtrace().map(String::from),
+            _ => Some(std::backtrace::Backtrace::force_capture().to_string()),
+        };
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let logs = RECENT_LOGS
+            .lock()
+            .map(|logs| logs.iter().cloned().collect())
+            .unwrap_or_default();
+        CrashReport {
+            app: self.app.clone(),
+            app_version: self.version.clone(),
+            aid_version: env!("CARGO_PKG_VERSION").to_string(),
+            features: features().iter().map(|f| f.to_string()).collect(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            timestamp,
+            name: error.name().to_string(),
+            code: error.code().to_string(),
+            chain: error.chain(),
+            backtrace,
+            logs,
+        }
+    }
+
+    /// The `write` method writes a report for `error` and returns the path of the file.
+    pub fn write(&self, error: &Bandage) -> Clean<PathBuf> {
+        let report = self.report(error);
+        std::fs::create_dir_all(&self.dir)?;
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        let path = self.dir.join(format!(
+            "crash-{nanos:020}-{}-{}.json",
+            std::process::id(),
+            WRITTEN.fetch_add(1, Ordering::Relaxed)
+        ));
+        let file = std::fs::File::create_new(&path)?;
+        serde_json::to_writer_pretty(std::io::BufWriter::new(file), &report)?;
+        Ok(path)
+    }
+
+    /// The `pending` method returns the paths of the reports waiting in the directory, oldest
+    /// first.
+    pub fn pending(&self) -> Clean<Vec<PathBuf>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut paths = Vec::new();
+        for entry in std::fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            let is_report = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("crash-") && name.ends_with(".json"));
+            if is_report {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+        Ok(paths)
+    }
+
+    /// The `read` method reads the report at `path`.
+    pub fn read<P: AsRef<Path>>(&self, path: P) -> Clean<CrashReport> {
+        let file = std::fs::File::open(path)?;
+        Ok(serde_json::from_reader(std::io::BufReader::new(file))?)
+    }
+
+    /// The `delete` method removes the report at `path`, such as after it has been sent.
+    pub fn delete<P: AsRef<Path>>(&self, path: P) -> Clean<()> {
+        Ok(std::fs::remove_file(path)?)
+    }
+}
+
+/// The `record_log` function adds a line to the recent logs included in crash reports, dropping
+/// the oldest line past `capacity`.  The line is scrubbed with [`scrub`] before it is kept.
+pub fn record_log(line: String, capacity: usize) {
+    let line = scrub(&line);
+    if let Ok(mut logs) = RECENT_LOGS.lock() {
+        while logs.len() >= capacity.max(1) {
+            logs.pop_front();
+        }
+        logs.push_back(line);
+    }
+}
+
+/// The `features` function returns the enabled features of this crate.
+pub fn features() -> Vec<&'static str> {
+    let features = [
+        ("bin", cfg!(feature = "bin")),
+        ("byte", cfg!(feature = "byte")),
+        ("csvs", cfg!(feature = "csvs")),
+        ("env", cfg!(feature = "env")),
+        ("gis", cfg!(feature = "gis")),
+        ("gpu", cfg!(feature = "gpu")),
+        ("hype", cfg!(feature = "hype")),
+        ("icon", cfg!(feature = "icon")),
+        ("id", cfg!(feature = "id")),
+        ("img", cfg!(feature = "img")),
+        ("oauth", cfg!(feature = "oauth")),
+        ("parse", cfg!(feature = "parse")),
+        ("plot", cfg!(feature = "plot")),
+        ("req", cfg!(feature = "req")),
+        ("redact", cfg!(feature = "redact")),
+        ("route", cfg!(feature = "route")),
+        ("serial", cfg!(feature = "serial")),
+        ("sql", cfg!(feature = "sql")),
+        ("stream", cfg!(feature = "stream")),
+        ("time", cfg!(feature = "time")),
+        ("trace", cfg!(feature = "trace")),
+        ("urls", cfg!(feature = "urls")),
+        ("win", cfg!(feature = "win")),
+    ];
+    features
+        .into_iter()
+        .filter_map(|(name, enabled)| enabled.then_some(name))
+        .collect()
+}
+
+/// Returns the platform data directory, falling back to the temporary directory.
+fn data_dir() -> PathBuf {
+    let home = || std::env::var_os("HOME").map(PathBuf::from);
+    let dir = if cfg!(windows) {
+        std::env::var_os("LOCALAPPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        home().map(|h| h.join("Library").join("Application Support"))
+    } else {
+        std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| home().map(|h| h.join(".local").join("share")))
+    };
+    dir.unwrap_or_else(std::env::temp_dir)
+}
+
+/// The `RecentLogs` layer records each `tracing` event with [`record_log`], so crash reports
+/// include the log lines leading up to the crash.
+/// [`TraceOptions::crash_reporting`](crate::trace::TraceOptions::crash_reporting) adds it to the
+/// subscriber installed by [`crate::trace::init`].
+#[cfg(feature = "trace")]
+#[cfg_attr(docsrs, doc(cfg(feature = "trace")))]
+#[derive(Debug, Copy, Clone)]
+pub struct RecentLogs {
+    capacity: usize,
+}
+
+#[cfg(feature = "trace")]
+impl RecentLogs {
+    /// The `new` method creates a layer that keeps the last `capacity` lines.
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity }
+    }
+}
+
+#[cfg(feature = "trace")]
+impl Default for RecentLogs {
+    fn default() -> Self {
+        Self::new(DEFAULT_LOG_CAPACITY)
+    }
+}
+
+#[cfg(feature = "trace")]
+impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for RecentLogs {
+    fn on_event(
+        &self,
+        event: &tracing::Event<'_>,
+        _ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        struct Line(String);
+        impl tracing::field::Visit for Line {
+            fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+                if field.name() == "message" {
+                    self.0.push_str(&format!(" {value:?}"));
+                } else {
+                    self.0.push_str(&format!(" {}={value:?}", field.name()));
+                }
+            }
+        }
+        let metadata = event.metadata();
+        let mut line = Line(format!("{} {}:", metadata.level(), metadata.target()));
+        event.record(&mut line);
+        record_log(line.0, self.capacity);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reporter(name: &str) -> CrashReporter {
+        let dir = std::env::temp_dir().join(format!("aid-crash-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        CrashReporter::new("aid-test", "1.0.0").dir(dir)
+    }
+
+    #[test]
+    fn reports_in_the_same_second_do_not_collide() {
+        let reporter = reporter("collide");
+        let error = Bandage::Hint("crashed".to_string());
+        let first = reporter.write(&error).unwrap();
+        let second = reporter.write(&error).unwrap();
+        assert_ne!(first, second);
+        assert_eq!(reporter.pending().unwrap(), vec![first.clone(), second]);
+        let report = reporter.read(&first).unwrap();
+        assert_eq!(report.app, "aid-test");
+        assert_eq!(report.chain, vec!["Hint: crashed".to_string()]);
+        std::fs::remove_dir_all(reporter.path()).unwrap();
+    }
+
+    #[cfg(feature = "redact")]
+    #[test]
+    fn recorded_logs_are_scrubbed() {
+        record_log(
+            "INFO db: connecting to postgres://app:hunter2@db/app".to_string(),
+            DEFAULT_LOG_CAPACITY,
+        );
+        let logs = RECENT_LOGS.lock().unwrap();
+        assert!(logs
+            .iter()
+            .any(|line| line.ends_with("postgres://app:[REDACTED]@db/app")));
+        assert!(logs.iter().all(|line| !line.contains("hunter2")));
+    }
+}
@@ -0,0 +1,183 @@
+//! The `env` module provides helpers for reading environment variables that report the name of
+//! the variable and the `.env` file loaded by `dotenvy` when they fail.
+use crate::prelude::{Bandage, Clean};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+static DOTENV_PATH: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+/// The `EnvErrorKind` enum describes why reading an environment variable failed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum EnvErrorKind {
+    /// The variable is not set.
+    Missing,
+    /// The variable is set but its value is not valid Unicode.
+    NotUnicode,
+    /// The variable is set but its value did not parse into the requested type.
+    Parse,
+    /// The `.env` file exists but could not be read or parsed.
+    Dotenv,
+}
+
+/// The `EnvError` struct holds the name of the environment variable that failed to read, the
+/// reason, and the path of the `.env` file loaded by `dotenvy`, if any.
+#[derive(Debug, thiserror::Error)]
+pub struct EnvError {
+    name: String,
+    kind: EnvErrorKind,
+    dotenv: Option<PathBuf>,
+    #[source]
+    source: Option<Box<dyn std::error::Error + Send + Sync>>,
+}
+
+impl EnvError {
+    /// The `name` method returns the name of the environment variable, or an empty string when
+    /// [`load`] failed before any variable was read.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The `kind` method returns the [`EnvErrorKind`] of the failure.
+    pub fn kind(&self) -> EnvErrorKind {
+        self.kind
+    }
+
+    /// The `dotenv` method returns the path of the `.env` file loaded by `dotenvy`, if one was
+    /// found.
+    pub fn dotenv(&self) -> Option<&Path> {
+        self.dotenv.as_deref()
+    }
+}
+
+impl std::fmt::Display for EnvError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.kind {
+            EnvErrorKind::Missing => write!(f, "{} is not set", self.name)?,
+            EnvErrorKind::NotUnicode => write!(f, "{} is not valid unicode", self.name)?,
+            EnvErrorKind::Parse => write!(f, "{} could not be parsed", self.name)?,
+            EnvErrorKind::Dotenv if self.name.is_empty() => {
+                return write!(f, "the .env file could not be loaded");
+            }
+            EnvErrorKind::Dotenv => {
+                return write!(f, "the .env file could not be loaded to read {}", self.name);
+            }
+        }
+        match &self.dotenv {
+            Some(path) => write!(f, " (loaded .env from {})", path.display()),
+            None => write!(f, " (no .env file loaded)"),
+        }
+    }
+}
+
+/// The `load` function loads the `.env` file from the current directory or its parents using
+/// `dotenvy`, and returns its path, or `None` if there is no `.env` file. The file is only loaded
+/// once; later calls return the path found by the first call.
+///
+/// A `.env` file that cannot be read or holds a malformed line returns an [`EnvError`] of kind
+/// [`EnvErrorKind::Dotenv`], and is tried again on the next call.
+pub fn load() -> Clean<Option<&'static Path>> {
+    dotenv("")
+}
+
+/// Loads the `.env` file, naming the variable `name` in the error if it fails.
+fn dotenv(name: &str) -> Clean<Option<&'static Path>> {
+    if let Some(path) = DOTENV_PATH.get() {
+        return Ok(path.as_deref());
+    }
+    let path = found(dotenvy::dotenv(), name)?;
+    Ok(DOTENV_PATH.get_or_init(|| path).as_deref())
+}
+
+/// Treats a missing `.env` file as absent and any other `dotenvy` error as an [`EnvError`].
+fn found(result: Result<PathBuf, dotenvy::Error>, name: &str) -> Clean<Option<PathBuf>> {
+    match result {
+        Ok(path) => Ok(Some(path)),
+        Err(dotenvy::Error::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(Bandage::EnvVar(EnvError {
+            name: name.to_string(),
+            kind: EnvErrorKind::Dotenv,
+            dotenv: None,
+            source: Some(Box::new(e)),
+        })),
+    }
+}
+
+/// The `var` function reads the environment variable `name`, loading the `.env` file first if it
+/// has not been loaded yet.
+pub fn var(name: &str) -> Clean<String> {
+    let dotenv = dotenv(name)?.map(Path::to_path_buf);
+    std::env::var(name).map_err(|e| {
+        let kind = match e {
+            std::env::VarError::NotPresent => EnvErrorKind::Missing,
+            std::env::VarError::NotUnicode(_) => EnvErrorKind::NotUnicode,
+        };
+        Bandage::EnvVar(EnvError {
+            name: name.to_string(),
+            kind,
+            dotenv,
+            source: Some(Box::new(e)),
+        })
+    })
+}
+
+/// The `parse` function reads the environment variable `name` and parses it into `T`.
+pub fn parse<T>(name: &str) -> Clean<T>
+where
+    T: std::str::FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    let value = var(name)?;
+    let dotenv = dotenv(name)?.map(Path::to_path_buf);
+    value.parse::<T>().map_err(|e| {
+        Bandage::EnvVar(EnvError {
+            name: name.to_string(),
+            kind: EnvErrorKind::Parse,
+            dotenv,
+            source: Some(Box::new(e)),
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_dotenv_is_absent() {
+        let missing = dotenvy::Error::Io(std::io::ErrorKind::NotFound.into());
+        assert!(found(Err(missing), "PORT").unwrap().is_none());
+        let path = PathBuf::from("/srv/app/.env");
+        assert_eq!(found(Ok(path.clone()), "PORT").unwrap(), Some(path));
+    }
+
+    #[test]
+    fn malformed_dotenv_is_an_error() {
+        let malformed = dotenvy::Error::LineParse("PORT 8080".to_string(), 5);
+        let Err(Bandage::EnvVar(error)) = found(Err(malformed), "PORT") else {
+            panic!("expected an environment error");
+        };
+        assert_eq!(error.kind(), EnvErrorKind::Dotenv);
+        assert_eq!(error.name(), "PORT");
+        assert_eq!(
+            error.to_string(),
+            "the .env file could not be loaded to read PORT"
+        );
+        let denied = dotenvy::Error::Io(std::io::ErrorKind::PermissionDenied.into());
+        let Err(Bandage::EnvVar(error)) = found(Err(denied), "") else {
+            panic!("expected an environment error");
+        };
+        assert_eq!(error.to_string(), "the .env file could not be loaded");
+    }
+
+    #[test]
+    fn errors_name_the_variable() {
+        let error = var("AID_TEST_UNSET_VARIABLE").unwrap_err();
+        let Bandage::EnvVar(error) = error else {
+            panic!("expected an environment error, got {error:?}");
+        };
+        assert_eq!(error.kind(), EnvErrorKind::Missing);
+        assert!(error
+            .to_string()
+            .starts_with("AID_TEST_UNSET_VARIABLE is not set"));
+    }
+}
@@ -1,212 +1,751 @@
 //! The `error` module defines a library-specific [`Bandage`] alias for `Error`, and an alias for
 //! Result, [`Clean`], using the `Error` alias.
+use crate::redact::{redact, redact_debug};
 
 /// The `Clean` type is an alias for `Result` using the library-defined [`Bandage`].
 pub type Clean<T> = Result<T, Bandage>;
 
 /// The `Bandage` enum is a library-specific error conversion.
+///
+/// Messages pass through [`redact`](crate::redact) when rendered with `Display` or `Debug`, so
+/// passwords, tokens and email addresses in the enclosed errors stay out of logs.
 // #[derive(Debug, derive_more::Error, derive_more::Display, derive_more::From)]
-#[derive(Debug, thiserror::Error)]
+#[derive(thiserror::Error)]
 pub enum Bandage {
-    /// The `Auth` variant indicates an error occurred during the authorization process.
-    #[error("Authorization failed.")]
-    Auth,
+    /// The `Auth` variant indicates an error occurred during the authorization process, with the
+    /// reason given by the enclosed [`AuthError`](crate::auth::AuthError).
+    #[error("Authorization failed: {}", redact(.0))]
+    Auth(#[from] crate::auth::AuthError),
     /// The `Env` variant represents error conversions from [`std::env::VarError`].
-    #[error("Could not read environmental variables from .env: {}", 0)]
+    #[error("Could not read environmental variables from .env: {}", redact(.0))]
     Env(#[from] std::env::VarError),
     /// The `FileName` variant indicates a malformed file name, from [`std::ffi::OsString`].
-    #[error("Bad file name {:?}.", 0)]
+    #[error("Bad file name {}.", redact_debug(.0))]
     FileName(std::ffi::OsString),
     /// The `Int` variant represents error conversions from [`std::num::ParseIntError`],
     /// indicating a failure to parse an integer from a string.
-    #[error("Could not parse integer from string: {}", 0)]
+    #[error("Could not parse integer from string: {}", redact(.0))]
     Int(#[from] std::num::ParseIntError),
     /// The `Io` variant represents error conversions from [`std::io::Error`].
-    #[error("Input/output error from std: {}", 0)]
+    #[error("Input/output error from std: {}", redact(.0))]
     Io(#[from] std::io::Error),
+    /// The `Panic` variant holds a panic caught by [`crate::panic::catch`].
+    #[error("Panic: {}", redact(.0))]
+    Panic(#[from] crate::panic::PanicError),
     /// A `Parse` indicates an error occurred during parsing.
     #[error("Parse error.")]
     Parse,
     /// The `UserBuild` indicates an error occurred using a builder pattern.
-    #[error("Value not provided for {value:?}.")]
+    #[error("Value not provided for {}.", redact_debug(.value))]
     UserBuild {
         /// The `value` field returns messages on missing parameters in the builder function.
         value: Vec<String>,
     },
     /// The `Utf8` variant converts a `std::str::Utf8Error`.
-    #[error("Utf8 error: {}", 0)]
+    #[error("Utf8 error: {}", redact(.0))]
     Utf8(#[from] std::str::Utf8Error),
+    /// The `Validation` variant holds every invalid field of a form or request, with the
+    /// constraint each broke.
+    #[error("Validation error: {}", redact(.0))]
+    Validation(#[from] crate::validate::ValidationError),
     /// The `Hint` variant encloses a message with an error.
-    #[error("Hint: {}", 0)]
+    #[error("Hint: {}", redact(.0))]
     Hint(String),
     /// The `Unknown` variant is a catch-all error variant for library operations.
     #[error("Unexpected error.")]
     Unknown,
+    /// The `Regex` variant converts errors from the `regex` crate, such as from a malformed
+    /// [`RedactRule`](crate::redact::RedactRule) pattern.
+    #[cfg(feature = "redact")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "redact")))]
+    #[error("Regex error: {}", redact(.0))]
+    Regex(#[from] regex::Error),
+    /// The `BatchThreshold` variant reports a batch job where the fraction of failed records
+    /// exceeded the threshold given to [`crate::partial::Partial::into_result`].
+    #[error("Batch failed: {}", redact(.0))]
+    BatchThreshold(#[from] crate::partial::ThresholdError),
+    /// The `Aggregate` variant holds every error from a batch, collected by
+    /// [`crate::collect::CleanIter::collect_all`].
+    #[error("Multiple errors: {}", redact(.0))]
+    Aggregate(#[from] crate::collect::AggregateError),
     /// The `BadIcon` results from a failed import of an icon image file into the Dioxus desktop
     /// app.
     #[cfg(feature = "icon")]
     #[cfg_attr(docsrs, doc(cfg(feature = "icon")))]
-    #[error("Icon loading error: {}", 0)]
+    #[error("Icon loading error: {}", redact(.0))]
     BadIcon(#[from] dioxus_desktop::tao::window::BadIcon),
+    /// The `Icon` variant names the file, image format and step that failed while loading an
+    /// icon with [`crate::icon::load`].
+    #[cfg(any(feature = "icon", feature = "win"))]
+    #[cfg_attr(docsrs, doc(cfg(any(feature = "icon", feature = "win"))))]
+    #[error("Could not load icon: {}", redact(.0))]
+    Icon(#[from] crate::icon::IconError),
     /// The `Bin` variant indicates a failure during binary encoding in crate `bincode`.
     #[cfg(feature = "bin")]
     #[cfg_attr(docsrs, doc(cfg(feature = "bin")))]
-    #[error("Could not serialize to binary: {}", 0)]
+    #[error("Could not serialize to binary: {}", redact(.0))]
     Bin(#[from] Box<bincode::ErrorKind>),
     /// Error returned by the byte_unit library
     #[cfg(feature = "byte")]
     #[cfg_attr(docsrs, doc(cfg(feature = "byte")))]
-    #[error("Byte conversion failed: {}", 0)]
+    #[error("Byte conversion failed: {}", redact(.0))]
     Byte(#[from] byte_unit::ParseError),
     /// The `Csv` variant converts an error returned by the `csv` crate. Currently indicates
     /// failure to generate a csv writer.
     #[cfg(feature = "csvs")]
     #[cfg_attr(docsrs, doc(cfg(feature = "csvs")))]
-    #[error("Could not make CSV writer: {}", 0)]
+    #[error("Could not make CSV writer: {}", redact(.0))]
     Csv(#[from] csv::Error),
     /// The `Http` variant converts an error from the `reqwest` crate.
     #[cfg(feature = "req")]
     #[cfg_attr(docsrs, doc(cfg(feature = "req")))]
-    #[error("HTTP request error: {}", 0)]
+    #[error("HTTP request error: {}", redact(.0))]
     Http(#[from] reqwest::Error),
     /// The `Image` variant converts an error from the `image` crate.
     #[cfg(feature = "img")]
     #[cfg_attr(docsrs, doc(cfg(feature = "img")))]
-    #[error("Image processing error: {}", 0)]
+    #[error("Image processing error: {}", redact(.0))]
     Image(#[from] image::error::ImageError),
     /// The `Oauth2` variant converts an error from the `oauth2` crate.
     #[cfg(feature = "oauth")]
     #[cfg_attr(docsrs, doc(cfg(feature = "oauth")))]
-    #[error("Oauth2 error: {}", 0)]
-    Oauth2(
-        #[from]
-        oauth2::RequestTokenError<
-            oauth2::reqwest::Error,
-            oauth2::StandardErrorResponse<oauth2::basic::BasicErrorResponseType>,
-        >,
-    ),
+    #[error("Oauth2 error: {}", redact(.0))]
+    Oauth2(#[from] crate::oauth::Oauth2Error),
     /// The `Serialize` variant converts errors from the `serde` crate.
     #[cfg(feature = "serial")]
     #[cfg_attr(docsrs, doc(cfg(feature = "serial")))]
-    #[error("Deserialize error: {}", 0)]
+    #[error("Deserialize error: {}", redact(.0))]
     Serialize(#[from] serde::de::value::Error),
     /// The `SerdeJson` variant converts an error from the `serde_json` crate.
     #[cfg(feature = "serial")]
     #[cfg_attr(docsrs, doc(cfg(feature = "serial")))]
-    #[error("Deserialization error: {}", 0)]
+    #[error("Deserialization error: {}", redact(.0))]
     SerdeJson(#[from] serde_json::Error),
     /// The `Sqlx` variant converts a general error from the `sqlx` crate.
     #[cfg(feature = "sql")]
     #[cfg_attr(docsrs, doc(cfg(feature = "sql")))]
-    #[error("Sqlx command error: {}", 0)]
+    #[error("Sqlx command error: {}", redact(.0))]
     Sqlx(#[from] sqlx::Error),
     /// The `Migrate` variant converts a migration error from the `sqlx` crate.
     #[cfg(feature = "sql")]
     #[cfg_attr(docsrs, doc(cfg(feature = "sql")))]
-    #[error("Sqlx migration error: {}", 0)]
+    #[error("Sqlx migration error: {}", redact(.0))]
     Migrate(#[from] sqlx::migrate::MigrateError),
     /// The `Uuid` variant converts an error from the `uuid` crate.
     #[cfg(feature = "id")]
     #[cfg_attr(docsrs, doc(cfg(feature = "id")))]
-    #[error("Uuid conversion failed: {}", 0)]
+    #[error("Uuid conversion failed: {}", redact(.0))]
     Uuid(#[from] uuid::Error),
     /// The `Url` variant converts an error from the `url` crate.
     #[cfg(feature = "urls")]
     #[cfg_attr(docsrs, doc(cfg(feature = "urls")))]
-    #[error("Parse error: {}", 0)]
+    #[error("Parse error: {}", redact(.0))]
     Url(#[from] url::ParseError),
     /// The `BitMap` variant converts an error from the `plotters_bitmap` crate.
     #[cfg(feature = "plot")]
     #[cfg_attr(docsrs, doc(cfg(feature = "plot")))]
-    #[error("Plotting backend error: {}", 0)]
+    #[error("Plotting backend error: {}", redact(.0))]
     BitMap(#[from] plotters_bitmap::BitMapBackendError),
     /// The `Plot` variant converts an error from the `plotters` crate.
     #[cfg(feature = "plot")]
     #[cfg_attr(docsrs, doc(cfg(feature = "plot")))]
-    #[error("Plotting drawing error: {}", 0)]
+    #[error("Plotting drawing error: {}", redact(.0))]
     Plot(#[from] plotters::drawing::DrawingAreaErrorKind<plotters_bitmap::BitMapBackendError>),
-    /// The `GeoJson` variant converts an error from the `geojson` crate.
+    /// The `GeoJson` variant converts an error from the `geojson` crate.  The error is boxed
+    /// because some `geojson` errors carry a whole feature, which would otherwise make every
+    /// [`Clean`] result at least 200 bytes; `?` still converts a bare `geojson::Error`.
     #[cfg(feature = "gis")]
     #[cfg_attr(docsrs, doc(cfg(feature = "gis")))]
-    #[error("Error reading geojson file: {}", 0)]
-    GeoJson(#[from] geojson::Error),
+    #[error("Error reading geojson file: {}", redact(.0))]
+    GeoJson(#[from] Box<geojson::Error>),
+    /// The `GeoJsonFeature` variant holds a `geojson` error with the index, identifier and JSON
+    /// pointer of the feature that failed, from [`crate::features::FeatureParser`].
+    #[cfg(feature = "gis")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "gis")))]
+    #[error("Error reading geojson feature: {}", redact(.0))]
+    GeoJsonFeature(#[from] crate::features::FeatureError),
+    /// The `Geometry` variant holds a problem with the geometry of a feature, such as an
+    /// unclosed ring or a coordinate outside the expected extent, from
+    /// [`crate::gis::GeometryValidator`].
+    #[cfg(feature = "gis")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "gis")))]
+    #[error("Invalid geometry: {}", redact(.0))]
+    Geometry(#[from] crate::gis::GeometryError),
+    /// The `Galileo` variant holds an error from the `galileo` map engine, with the tile or layer
+    /// that failed and whether retrying may help.
+    #[cfg(feature = "gis")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "gis")))]
+    #[error("Map engine error: {}", redact(.0))]
+    Galileo(#[from] crate::map::MapError),
     /// The `TraceInit` variant converts an error from the `tracing_subscriber` crate.
     #[cfg(feature = "trace")]
     #[cfg_attr(docsrs, doc(cfg(feature = "trace")))]
-    #[error("Problem initializing subscriber: {}", 0)]
+    #[error("Problem initializing subscriber: {}", redact(.0))]
     TraceInit(#[from] tracing_subscriber::util::TryInitError),
+    /// The `TraceFilter` variant converts an error parsing `tracing_subscriber` filter directives.
+    #[cfg(feature = "trace")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "trace")))]
+    #[error("Invalid trace filter directives: {}", redact(.0))]
+    TraceFilter(#[from] tracing_subscriber::filter::ParseError),
     /// The `Axum` variants converts an *axum::Error* from the `axum` crate.
     #[cfg(feature = "route")]
     #[cfg_attr(docsrs, doc(cfg(feature = "route")))]
-    #[error("Axum error: {}", 0)]
+    #[error("Axum error: {}", redact(.0))]
     Axum(#[from] axum::Error),
     /// The `AxumHttp` variant converts an axum::http error from the `axum` crate.
     #[cfg(feature = "route")]
     #[cfg_attr(docsrs, doc(cfg(feature = "route")))]
-    #[error("Axum http error: {}", 0)]
+    #[error("Axum http error: {}", redact(.0))]
     AxumHttp(#[from] axum::http::Error),
     /// The `Hyper` variant converts a `hyper_util::error::Error` from the `hyper_util` crate.
     #[cfg(feature = "hype")]
     #[cfg_attr(docsrs, doc(cfg(feature = "hype")))]
-    #[error("Legacy client error: {}", 0)]
+    #[error("Legacy client error: {}", redact(.0))]
     Hyper(#[from] hyper::Error),
     /// The `HyperUtil` variant converts a `hyper_util::client::legacy::Error` from the `hyper_util` crate.
     #[cfg(feature = "hype")]
     #[cfg_attr(docsrs, doc(cfg(feature = "hype")))]
-    #[error("Legacy client error: {}", 0)]
+    #[error("Legacy client error: {}", redact(.0))]
     HyperUtil(#[from] hyper_util::client::legacy::Error),
     /// The `HyperUtil` variant converts a `hyper_util::client::legacy::Error` from the `hyper_util` crate.
     #[cfg(feature = "gis")]
     #[cfg_attr(docsrs, doc(cfg(feature = "gis")))]
-    #[error("Shapefile error: {}", 0)]
+    #[error("Shapefile error: {}", redact(.0))]
     Shapefile(#[from] shapefile::Error),
+    /// The `ShapefileRecord` variant holds a shapefile error with the file, record, shape type
+    /// and attribute field that failed, from [`crate::shape::ShapefileReader`].
+    #[cfg(feature = "gis")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "gis")))]
+    #[error("Shapefile error: {}", redact(.0))]
+    ShapefileRecord(#[from] crate::shape::ShapefileError),
     /// The `EventLoop` variant converts a [`winit::error::EventLoopError`] from the `winit` crate.
     #[cfg(feature = "win")]
     #[cfg_attr(docsrs, doc(cfg(feature = "win")))]
-    #[error("Winit event loop error: {}", 0)]
+    #[error("Winit event loop error: {}", redact(.0))]
     EventLoop(#[from] winit::error::EventLoopError),
     /// The `WinOs` variant converts a [`winit::error::OsError`] from the `winit` crate.
     #[cfg(feature = "win")]
     #[cfg_attr(docsrs, doc(cfg(feature = "win")))]
-    #[error("OS error: {}", 0)]
+    #[error("OS error: {}", redact(.0))]
     WinOs(#[from] winit::error::OsError),
     /// The `WinitIcon` variant converts a [`winit::window::BadIcon`] from the `winit` crate.
     #[cfg(feature = "win")]
     #[cfg_attr(docsrs, doc(cfg(feature = "win")))]
-    #[error("Winit icon error: {}", 0)]
+    #[error("Winit icon error: {}", redact(.0))]
     WinitIcon(#[from] winit::window::BadIcon),
+    /// The `WinitExternal` variant converts a [`winit::error::ExternalError`] from the `winit`
+    /// crate.
+    #[cfg(feature = "win")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "win")))]
+    #[error("Winit error: {}", redact(.0))]
+    WinitExternal(#[from] winit::error::ExternalError),
+    /// The `WinitNotSupported` variant converts a [`winit::error::NotSupportedError`] from the
+    /// `winit` crate.
+    #[cfg(feature = "win")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "win")))]
+    #[error("Winit error: {}", redact(.0))]
+    WinitNotSupported(#[from] winit::error::NotSupportedError),
+    /// The `EventLoopClosed` variant converts a [`winit::event_loop::EventLoopClosed`], dropping
+    /// the event that could not be sent.
+    #[cfg(feature = "win")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "win")))]
+    #[error("Tried to wake up a closed event loop.")]
+    EventLoopClosed,
     /// The `WgpuSurface` variant converts a [`wgpu::CreateSurfaceError`] from the `wgpu` crate.
     #[cfg(feature = "gpu")]
     #[cfg_attr(docsrs, doc(cfg(feature = "gpu")))]
-    #[error("From wgpu: {}", 0)]
+    #[error("From wgpu: {}", redact(.0))]
     WgpuSurface(#[from] wgpu::CreateSurfaceError),
     /// The `WgpuDevice` variant converts a [`wgpu::RequestDeviceError`] from the `wgpu` crate.
     #[cfg(feature = "gpu")]
     #[cfg_attr(docsrs, doc(cfg(feature = "gpu")))]
-    #[error("From wgpu: {}", 0)]
+    #[error("From wgpu: {}", redact(.0))]
     WgpuDevice(#[from] wgpu::RequestDeviceError),
+    /// The `WgpuFrame` variant converts a [`wgpu::SurfaceError`] from acquiring the next frame of
+    /// a surface.
+    #[cfg(feature = "gpu")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "gpu")))]
+    #[error("From wgpu: {}", redact(.0))]
+    WgpuFrame(#[from] wgpu::SurfaceError),
+    /// The `WgpuBuffer` variant converts a [`wgpu::BufferAsyncError`] from mapping a buffer.
+    #[cfg(feature = "gpu")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "gpu")))]
+    #[error("From wgpu: {}", redact(.0))]
+    WgpuBuffer(#[from] wgpu::BufferAsyncError),
+    /// The `WgpuAdapter` variant indicates no GPU adapter matched a request, from
+    /// [`crate::gpu::request_adapter`].
+    #[cfg(feature = "gpu")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "gpu")))]
+    #[error("From wgpu: {}", redact(.0))]
+    WgpuAdapter(#[from] crate::gpu::NoAdapterError),
+    /// The `WgpuUncaptured` variant converts a [`wgpu::Error`] passed to the uncaptured error
+    /// handler of a device, such as by [`crate::gpu::forward_uncaptured`].
+    #[cfg(feature = "gpu")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "gpu")))]
+    #[error("Uncaptured wgpu error: {}", redact(.0))]
+    WgpuUncaptured(#[from] crate::gpu::UncapturedError),
     /// The `Nom` variant converts errors from the `nom` crate.
     #[cfg(feature = "parse")]
     #[cfg_attr(docsrs, doc(cfg(feature = "parse")))]
-    #[error("Nom error: {}", 0)]
+    #[error("Nom error: {}", redact(.0))]
     Nom(String),
     /// The `Tiberius` variant converts errors from the `tiberius` crate.
     #[cfg(feature = "sql")]
     #[cfg_attr(docsrs, doc(cfg(feature = "sql")))]
-    #[error("Tiberius error: {}", 0)]
+    #[error("Tiberius error: {}", redact(.0))]
     Tiberius(#[from] tiberius::error::Error),
     /// The `Dotenv` variant converts errors from the `dotenvy` crate.
     #[cfg(feature = "env")]
     #[cfg_attr(docsrs, doc(cfg(feature = "env")))]
-    #[error("Dotenvy error: {}", 0)]
+    #[error("Dotenvy error: {}", redact(.0))]
     Dotenv(#[from] dotenvy::Error),
+    /// The `Config` variant lists every problem found while loading configuration.
+    #[cfg(all(feature = "env", feature = "serial"))]
+    #[cfg_attr(docsrs, doc(cfg(all(feature = "env", feature = "serial"))))]
+    #[error("Configuration error: {}", redact(.0))]
+    Config(#[from] crate::config::ConfigError),
+    /// The `EnvVar` variant names an environment variable that was missing or malformed.
+    #[cfg(feature = "env")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "env")))]
+    #[error("Environment variable error: {}", redact(.0))]
+    EnvVar(#[from] crate::env::EnvError),
+    /// The `DateParse` variant holds the input and the error from every format tried when parsing
+    /// a date or time.
+    #[cfg(feature = "time")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "time")))]
+    #[error("Could not parse date or time: {}", redact(.0))]
+    DateParse(#[from] crate::time::DateParseError),
     /// The `Jiff` variant converts errors from the `jiff` crate.
     #[cfg(feature = "time")]
     #[cfg_attr(docsrs, doc(cfg(feature = "time")))]
-    #[error("Jiff error: {}", 0)]
+    #[error("Jiff error: {}", redact(.0))]
     Jiff(#[from] jiff::Error),
 }
 
+/// Generates [`BandageKind`], its names and codes, and [`Bandage::kind`] from a list of the
+/// variants of [`Bandage`] with their codes, keeping each variant's feature gate.
+macro_rules! kinds {
+    ($($(#[cfg($cfg:meta)])? $name:ident => $code:literal,)*) => {
+        /// The `BandageKind` enum names each variant of [`Bandage`] without its payload, for
+        /// matching, filtering, and use as a map key or configuration value.
+        ///
+        /// Configuration and JSON write a kind as its [`code`](BandageKind::code), such as
+        /// `"tiberius"`, matching logs and reports.  Parsing and deserializing also accept the
+        /// [`name`](BandageKind::name), such as `"Tiberius"`.
+        #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub enum BandageKind {
+            $(
+                #[doc = concat!("The kind of [`Bandage::", stringify!($name), "`].")]
+                $(#[cfg($cfg)] #[cfg_attr(docsrs, doc(cfg($cfg)))])?
+                $name,
+            )*
+        }
+
+        impl BandageKind {
+            /// The `ALL` constant lists every kind enabled by the current features, in
+            /// declaration order.
+            pub const ALL: &'static [BandageKind] = &[$($(#[cfg($cfg)])? Self::$name,)*];
+
+            /// The `name` method returns the name of the variant, such as `"Io"`.
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $($(#[cfg($cfg)])? Self::$name => stringify!($name),)*
+                }
+            }
+
+            /// The `code` method returns the snake case code of the variant, such as `"io"`.
+            pub fn code(&self) -> &'static str {
+                match self {
+                    $($(#[cfg($cfg)])? Self::$name => $code,)*
+                }
+            }
+        }
+
+        impl Bandage {
+            /// The `kind` method returns the [`BandageKind`] of the variant.
+            pub fn kind(&self) -> BandageKind {
+                match self {
+                    $($(#[cfg($cfg)])? Self::$name { .. } => BandageKind::$name,)*
+                }
+            }
+        }
+    };
+}
+
+kinds! {
+    Auth => "auth",
+    Env => "env",
+    FileName => "file_name",
+    Int => "int",
+    Io => "io",
+    Panic => "panic",
+    Parse => "parse",
+    UserBuild => "user_build",
+    Validation => "validation",
+    Utf8 => "utf8",
+    Hint => "hint",
+    Unknown => "unknown",
+    #[cfg(feature = "redact")]
+    Regex => "regex",
+    BatchThreshold => "batch_threshold",
+    Aggregate => "aggregate",
+    #[cfg(feature = "icon")]
+    BadIcon => "bad_icon",
+    #[cfg(any(feature = "icon", feature = "win"))]
+    Icon => "icon",
+    #[cfg(feature = "bin")]
+    Bin => "bin",
+    #[cfg(feature = "byte")]
+    Byte => "byte",
+    #[cfg(feature = "csvs")]
+    Csv => "csv",
+    #[cfg(feature = "req")]
+    Http => "http",
+    #[cfg(feature = "img")]
+    Image => "image",
+    #[cfg(feature = "oauth")]
+    Oauth2 => "oauth2",
+    #[cfg(feature = "serial")]
+    Serialize => "serialize",
+    #[cfg(feature = "serial")]
+    SerdeJson => "serde_json",
+    #[cfg(feature = "sql")]
+    Sqlx => "sqlx",
+    #[cfg(feature = "sql")]
+    Migrate => "migrate",
+    #[cfg(feature = "id")]
+    Uuid => "uuid",
+    #[cfg(feature = "urls")]
+    Url => "url",
+    #[cfg(feature = "plot")]
+    BitMap => "bit_map",
+    #[cfg(feature = "plot")]
+    Plot => "plot",
+    #[cfg(feature = "gis")]
+    GeoJson => "geo_json",
+    #[cfg(feature = "gis")]
+    GeoJsonFeature => "geo_json_feature",
+    #[cfg(feature = "gis")]
+    Geometry => "geometry",
+    #[cfg(feature = "gis")]
+    Galileo => "galileo",
+    #[cfg(feature = "trace")]
+    TraceInit => "trace_init",
+    #[cfg(feature = "trace")]
+    TraceFilter => "trace_filter",
+    #[cfg(feature = "route")]
+    Axum => "axum",
+    #[cfg(feature = "route")]
+    AxumHttp => "axum_http",
+    #[cfg(feature = "hype")]
+    Hyper => "hyper",
+    #[cfg(feature = "hype")]
+    HyperUtil => "hyper_util",
+    #[cfg(feature = "gis")]
+    Shapefile => "shapefile",
+    #[cfg(feature = "gis")]
+    ShapefileRecord => "shapefile_record",
+    #[cfg(feature = "win")]
+    EventLoop => "event_loop",
+    #[cfg(feature = "win")]
+    WinOs => "win_os",
+    #[cfg(feature = "win")]
+    WinitIcon => "winit_icon",
+    #[cfg(feature = "win")]
+    WinitExternal => "winit_external",
+    #[cfg(feature = "win")]
+    WinitNotSupported => "winit_not_supported",
+    #[cfg(feature = "win")]
+    EventLoopClosed => "event_loop_closed",
+    #[cfg(feature = "gpu")]
+    WgpuSurface => "wgpu_surface",
+    #[cfg(feature = "gpu")]
+    WgpuDevice => "wgpu_device",
+    #[cfg(feature = "gpu")]
+    WgpuFrame => "wgpu_frame",
+    #[cfg(feature = "gpu")]
+    WgpuBuffer => "wgpu_buffer",
+    #[cfg(feature = "gpu")]
+    WgpuAdapter => "wgpu_adapter",
+    #[cfg(feature = "gpu")]
+    WgpuUncaptured => "wgpu_uncaptured",
+    #[cfg(feature = "parse")]
+    Nom => "nom",
+    #[cfg(feature = "sql")]
+    Tiberius => "tiberius",
+    #[cfg(feature = "env")]
+    Dotenv => "dotenv",
+    #[cfg(all(feature = "env", feature = "serial"))]
+    Config => "config",
+    #[cfg(feature = "env")]
+    EnvVar => "env_var",
+    #[cfg(feature = "time")]
+    DateParse => "date_parse",
+    #[cfg(feature = "time")]
+    Jiff => "jiff",
+}
+
+impl std::fmt::Display for BandageKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl std::str::FromStr for BandageKind {
+    type Err = Bandage;
+
+    /// Parses the name or code of a kind, such as `"Tiberius"` or `"tiberius"`.
+    fn from_str(s: &str) -> Clean<Self> {
+        Self::ALL
+            .iter()
+            .find(|kind| kind.name() == s || kind.code() == s)
+            .copied()
+            .ok_or_else(|| Bandage::Hint(format!("unknown error kind `{s}`")))
+    }
+}
+
+#[cfg(any(feature = "serial", feature = "route"))]
+impl serde::Serialize for BandageKind {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.code())
+    }
+}
+
+#[cfg(any(feature = "serial", feature = "route"))]
+impl<'de> serde::Deserialize<'de> for BandageKind {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = std::borrow::Cow::<str>::deserialize(deserializer)?;
+        text.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl Bandage {
+    /// The `name` method returns the name of the variant, such as `"Io"`.
+    pub fn name(&self) -> &'static str {
+        self.kind().name()
+    }
+
+    /// The `code` method returns a stable, snake case code for the variant, such as `"io"`, for
+    /// use in logs and reports.
+    pub fn code(&self) -> &'static str {
+        self.kind().code()
+    }
+
+    /// The `is_retryable` method returns `true` if the error is likely transient, such as a
+    /// timeout or a dropped connection, so the operation may succeed if tried again.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Io(e) => matches!(
+                e.kind(),
+                std::io::ErrorKind::TimedOut
+                    | std::io::ErrorKind::Interrupted
+                    | std::io::ErrorKind::WouldBlock
+                    | std::io::ErrorKind::ConnectionReset
+                    | std::io::ErrorKind::ConnectionAborted
+                    | std::io::ErrorKind::ConnectionRefused
+            ),
+            #[cfg(feature = "req")]
+            Self::Http(e) => {
+                e.is_timeout()
+                    || e.is_connect()
+                    || e.status().is_some_and(|s| {
+                        s.is_server_error() || s == reqwest::StatusCode::TOO_MANY_REQUESTS
+                    })
+            }
+            #[cfg(feature = "oauth")]
+            Self::Oauth2(e) => e.kind() == crate::oauth::Oauth2ErrorKind::Request,
+            #[cfg(feature = "sql")]
+            Self::Sqlx(e) => matches!(e, sqlx::Error::PoolTimedOut | sqlx::Error::Io(_)),
+            #[cfg(feature = "sql")]
+            Self::Tiberius(e) => matches!(e, tiberius::error::Error::Io { .. }),
+            #[cfg(feature = "hype")]
+            Self::Hyper(e) => e.is_timeout() || e.is_incomplete_message(),
+            #[cfg(feature = "hype")]
+            Self::HyperUtil(e) => e.is_connect(),
+            #[cfg(feature = "gis")]
+            Self::Galileo(e) => e.is_transient(),
+            #[cfg(feature = "gpu")]
+            Self::WgpuFrame(e) => !matches!(e, wgpu::SurfaceError::OutOfMemory),
+            Self::Aggregate(e) => {
+                !e.errors().is_empty() && e.errors().iter().all(Self::is_retryable)
+            }
+            _ => false,
+        }
+    }
+
+    /// The `chain` method returns the message of the error followed by the message of each error
+    /// in its source chain.
+    pub fn chain(&self) -> Vec<String> {
+        let mut chain = vec![self.to_string()];
+        let mut source = std::error::Error::source(self);
+        while let Some(error) = source {
+            chain.push(redact(error));
+            source = error.source();
+        }
+        chain
+    }
+}
+
+impl std::fmt::Debug for Bandage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let payload: &dyn std::fmt::Debug = match self {
+            Self::Auth(e) => e,
+            Self::Env(e) => e,
+            Self::FileName(e) => e,
+            Self::Int(e) => e,
+            Self::Io(e) => e,
+            Self::Panic(e) => e,
+            Self::Parse => return f.write_str(self.name()),
+            Self::UserBuild { value } => {
+                return f
+                    .debug_struct("UserBuild")
+                    .field("value", &Scrubbed(value))
+                    .finish();
+            }
+            Self::Validation(e) => e,
+            Self::Utf8(e) => e,
+            Self::Hint(e) => e,
+            Self::Unknown => return f.write_str(self.name()),
+            #[cfg(feature = "redact")]
+            Self::Regex(e) => e,
+            Self::BatchThreshold(e) => e,
+            Self::Aggregate(e) => e,
+            #[cfg(feature = "icon")]
+            Self::BadIcon(e) => e,
+            #[cfg(any(feature = "icon", feature = "win"))]
+            Self::Icon(e) => e,
+            #[cfg(feature = "bin")]
+            Self::Bin(e) => e,
+            #[cfg(feature = "byte")]
+            Self::Byte(e) => e,
+            #[cfg(feature = "csvs")]
+            Self::Csv(e) => e,
+            #[cfg(feature = "req")]
+            Self::Http(e) => e,
+            #[cfg(feature = "img")]
+            Self::Image(e) => e,
+            #[cfg(feature = "oauth")]
+            Self::Oauth2(e) => e,
+            #[cfg(feature = "serial")]
+            Self::Serialize(e) => e,
+            #[cfg(feature = "serial")]
+            Self::SerdeJson(e) => e,
+            #[cfg(feature = "sql")]
+            Self::Sqlx(e) => e,
+            #[cfg(feature = "sql")]
+            Self::Migrate(e) => e,
+            #[cfg(feature = "id")]
+            Self::Uuid(e) => e,
+            #[cfg(feature = "urls")]
+            Self::Url(e) => e,
+            #[cfg(feature = "plot")]
+            Self::BitMap(e) => e,
+            #[cfg(feature = "plot")]
+            Self::Plot(e) => e,
+            #[cfg(feature = "gis")]
+            Self::GeoJson(e) => e,
+            #[cfg(feature = "gis")]
+            Self::GeoJsonFeature(e) => e,
+            #[cfg(feature = "gis")]
+            Self::Geometry(e) => e,
+            #[cfg(feature = "gis")]
+            Self::Galileo(e) => e,
+            #[cfg(feature = "trace")]
+            Self::TraceInit(e) => e,
+            #[cfg(feature = "trace")]
+            Self::TraceFilter(e) => e,
+            #[cfg(feature = "route")]
+            Self::Axum(e) => e,
+            #[cfg(feature = "route")]
+            Self::AxumHttp(e) => e,
+            #[cfg(feature = "hype")]
+            Self::Hyper(e) => e,
+            #[cfg(feature = "hype")]
+            Self::HyperUtil(e) => e,
+            #[cfg(feature = "gis")]
+            Self::Shapefile(e) => e,
+            #[cfg(feature = "gis")]
+            Self::ShapefileRecord(e) => e,
+            #[cfg(feature = "win")]
+            Self::EventLoop(e) => e,
+            #[cfg(feature = "win")]
+            Self::WinOs(e) => e,
+            #[cfg(feature = "win")]
+            Self::WinitIcon(e) => e,
+            #[cfg(feature = "win")]
+            Self::WinitExternal(e) => e,
+            #[cfg(feature = "win")]
+            Self::WinitNotSupported(e) => e,
+            #[cfg(feature = "win")]
+            Self::EventLoopClosed => return f.write_str(self.name()),
+            #[cfg(feature = "gpu")]
+            Self::WgpuSurface(e) => e,
+            #[cfg(feature = "gpu")]
+            Self::WgpuDevice(e) => e,
+            #[cfg(feature = "gpu")]
+            Self::WgpuFrame(e) => e,
+            #[cfg(feature = "gpu")]
+            Self::WgpuBuffer(e) => e,
+            #[cfg(feature = "gpu")]
+            Self::WgpuAdapter(e) => e,
+            #[cfg(feature = "gpu")]
+            Self::WgpuUncaptured(e) => e,
+            #[cfg(feature = "parse")]
+            Self::Nom(e) => e,
+            #[cfg(feature = "sql")]
+            Self::Tiberius(e) => e,
+            #[cfg(feature = "env")]
+            Self::Dotenv(e) => e,
+            #[cfg(all(feature = "env", feature = "serial"))]
+            Self::Config(e) => e,
+            #[cfg(feature = "env")]
+            Self::EnvVar(e) => e,
+            #[cfg(feature = "time")]
+            Self::DateParse(e) => e,
+            #[cfg(feature = "time")]
+            Self::Jiff(e) => e,
+        };
+        f.debug_tuple(self.name())
+            .field(&Scrubbed(payload))
+            .finish()
+    }
+}
+
+/// Writes the redacted `Debug` output of the enclosed value, keeping the `{:#?}` flag.
+struct Scrubbed<'a, T: std::fmt::Debug + ?Sized>(&'a T);
+
+impl<T: std::fmt::Debug + ?Sized> std::fmt::Debug for Scrubbed<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if f.alternate() {
+            f.write_str(&crate::redact::scrub(&format!("{:#?}", self.0)))
+        } else {
+            f.write_str(&redact_debug(self.0))
+        }
+    }
+}
+
+impl From<crate::auth::AuthReason> for Bandage {
+    fn from(reason: crate::auth::AuthReason) -> Self {
+        Self::Auth(reason.into())
+    }
+}
+
+#[cfg(feature = "route")]
+#[cfg_attr(docsrs, doc(cfg(feature = "route")))]
+impl axum::response::IntoResponse for Bandage {
+    fn into_response(self) -> axum::response::Response {
+        match self {
+            Self::Auth(error) => error.into_response(),
+            Self::Validation(error) => error.into_response(),
+            _ => (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                self.user_message(),
+            )
+                .into_response(),
+        }
+    }
+}
+
 #[cfg(feature = "gis")]
 #[cfg_attr(docsrs, doc(cfg(feature = "gis")))]
 impl<T> From<std::sync::PoisonError<T>> for Bandage {
@@ -215,6 +754,50 @@ impl<T> From<std::sync::PoisonError<T>> for Bandage {
     }
 }
 
+#[cfg(feature = "oauth")]
+#[cfg_attr(docsrs, doc(cfg(feature = "oauth")))]
+impl<RE, T> From<oauth2::RequestTokenError<RE, T>> for Bandage
+where
+    RE: std::error::Error + Send + Sync + 'static,
+    T: oauth2::ErrorResponse + Send + Sync + 'static,
+{
+    fn from(input: oauth2::RequestTokenError<RE, T>) -> Self {
+        Self::Oauth2(input.into())
+    }
+}
+
+#[cfg(feature = "gis")]
+#[cfg_attr(docsrs, doc(cfg(feature = "gis")))]
+impl From<geojson::Error> for Bandage {
+    fn from(error: geojson::Error) -> Self {
+        Self::GeoJson(Box::new(error))
+    }
+}
+
+#[cfg(feature = "gis")]
+#[cfg_attr(docsrs, doc(cfg(feature = "gis")))]
+impl From<galileo::error::GalileoError> for Bandage {
+    fn from(error: galileo::error::GalileoError) -> Self {
+        Self::Galileo(error.into())
+    }
+}
+
+#[cfg(feature = "win")]
+#[cfg_attr(docsrs, doc(cfg(feature = "win")))]
+impl<T> From<winit::event_loop::EventLoopClosed<T>> for Bandage {
+    fn from(_: winit::event_loop::EventLoopClosed<T>) -> Self {
+        Self::EventLoopClosed
+    }
+}
+
+#[cfg(feature = "gpu")]
+#[cfg_attr(docsrs, doc(cfg(feature = "gpu")))]
+impl From<wgpu::Error> for Bandage {
+    fn from(error: wgpu::Error) -> Self {
+        Self::WgpuUncaptured(error.into())
+    }
+}
+
 #[cfg(feature = "parse")]
 #[cfg_attr(docsrs, doc(cfg(feature = "parse")))]
 impl<'a> From<nom::Err<nom::error::Error<&'a str>>> for Bandage {
@@ -223,3 +806,44 @@ impl<'a> From<nom::Err<nom::error::Error<&'a str>>> for Bandage {
         Self::Nom(message)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kinds_are_unique() {
+        let kinds: std::collections::HashSet<_> = BandageKind::ALL.iter().collect();
+        let names: std::collections::HashSet<_> =
+            BandageKind::ALL.iter().map(|k| k.name()).collect();
+        let codes: std::collections::HashSet<_> =
+            BandageKind::ALL.iter().map(|k| k.code()).collect();
+        assert_eq!(kinds.len(), BandageKind::ALL.len());
+        assert_eq!(names.len(), BandageKind::ALL.len());
+        assert_eq!(codes.len(), BandageKind::ALL.len());
+    }
+
+    #[test]
+    fn names_and_codes_parse() {
+        for kind in BandageKind::ALL {
+            assert_eq!(kind.name().parse::<BandageKind>().unwrap(), *kind);
+            assert_eq!(kind.code().parse::<BandageKind>().unwrap(), *kind);
+        }
+        assert!("NoSuchKind".parse::<BandageKind>().is_err());
+        assert_eq!(Bandage::Unknown.kind(), BandageKind::Unknown);
+        assert_eq!(BandageKind::FileName.to_string(), "FileName");
+    }
+
+    #[cfg(feature = "serial")]
+    #[test]
+    fn kinds_serialize_as_codes() {
+        for kind in BandageKind::ALL {
+            let json = serde_json::to_string(kind).unwrap();
+            assert_eq!(json, format!("\"{}\"", kind.code()));
+            assert_eq!(serde_json::from_str::<BandageKind>(&json).unwrap(), *kind);
+        }
+        let kind: BandageKind = serde_json::from_str("\"UserBuild\"").unwrap();
+        assert_eq!(kind, BandageKind::UserBuild);
+        assert!(serde_json::from_str::<BandageKind>("\"nope\"").is_err());
+    }
+}
@@ -0,0 +1,350 @@
+//! The `features` module parses a GeoJSON `FeatureCollection` one feature at a time, so every
+//! bad feature in an uploaded file is reported at once, by index, identifier and a JSON pointer
+//! to the member that failed.
+use crate::prelude::{Bandage, Clean};
+use geojson::{Feature, JsonValue};
+
+/// The `FeatureError` struct holds a `geojson` error with the feature and member that failed.
+#[derive(Debug, thiserror::Error)]
+pub struct FeatureError {
+    index: usize,
+    id: Option<String>,
+    pointer: String,
+    #[source]
+    source: Box<geojson::Error>,
+}
+
+impl FeatureError {
+    /// The `index` method returns the zero-based index of the feature in the collection.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// The `id` method returns the `id` of the feature, or the value of the identifying
+    /// property set by [`FeatureParser::key`], if present.
+    pub fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    /// The `pointer` method returns a JSON pointer to the member that failed, such as
+    /// `/features/812/geometry/coordinates/0`.
+    pub fn pointer(&self) -> &str {
+        &self.pointer
+    }
+
+    /// The `geojson` method returns the enclosed [`geojson::Error`].
+    pub fn geojson(&self) -> &geojson::Error {
+        &self.source
+    }
+}
+
+impl std::fmt::Display for FeatureError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "feature {}", self.index)?;
+        if let Some(id) = &self.id {
+            write!(f, " ({id})")?;
+        }
+        write!(f, " at {}: {}", self.pointer, self.source)
+    }
+}
+
+/// The `FeatureBatch` struct holds the features parsed from a collection and the errors from
+/// features that failed.
+#[derive(Debug, Default)]
+pub struct FeatureBatch {
+    /// The `features` field holds each parsed feature with its index in the collection.
+    pub features: Vec<(usize, Feature)>,
+    /// The `errors` field holds a [`Bandage::GeoJsonFeature`] for each feature that failed.
+    pub errors: Vec<Bandage>,
+}
+
+/// The `FeatureParser` struct parses the features of a GeoJSON `FeatureCollection`,
+/// continuing past features that fail.
+#[derive(Debug, Clone, Default)]
+pub struct FeatureParser {
+    key: Option<String>,
+}
+
+impl FeatureParser {
+    /// The `new` method creates a parser that identifies features by their `id`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The `key` method sets a property, such as a parcel number, that identifies features
+    /// without an `id`.
+    pub fn key<S: Into<String>>(mut self, key: S) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+
+    /// The `parse` method parses `text` as a `FeatureCollection`.
+    ///
+    /// Malformed JSON, or JSON that is not a `FeatureCollection`, fails as a whole with a
+    /// [`Bandage::GeoJson`]. Features that fail are collected in [`FeatureBatch::errors`].
+    pub fn parse(&self, text: &str) -> Clean<FeatureBatch> {
+        let value: JsonValue = serde_json::from_str(text).map_err(geojson::Error::from)?;
+        self.parse_value(value)
+    }
+
+    /// The `parse_value` method parses a JSON value as a `FeatureCollection`.
+    pub fn parse_value(&self, value: JsonValue) -> Clean<FeatureBatch> {
+        let JsonValue::Object(mut object) = value else {
+            return Err(geojson::Error::GeoJsonExpectedObject(value).into());
+        };
+        match object.get("type").and_then(JsonValue::as_str) {
+            Some("FeatureCollection") => {}
+            Some(actual) => {
+                return Err(geojson::Error::ExpectedType {
+                    expected: "FeatureCollection".to_string(),
+                    actual: actual.to_string(),
+                }
+                .into());
+            }
+            None => return Err(geojson::Error::ExpectedProperty("type".to_string()).into()),
+        }
+        let features = match object.remove("features") {
+            Some(JsonValue::Array(features)) => features,
+            Some(other) => {
+                return Err(geojson::Error::ExpectedArrayValue(other.to_string()).into());
+            }
+            None => return Err(geojson::Error::ExpectedProperty("features".to_string()).into()),
+        };
+        let mut batch = FeatureBatch::default();
+        for (index, value) in features.into_iter().enumerate() {
+            // The parser takes the feature by value, so what an error needs is read first rather
+            // than cloning every feature.
+            let id = self.identify(&value);
+            let pointer = locate(&value);
+            match Feature::from_json_value(value) {
+                Ok(feature) => batch.features.push((index, feature)),
+                Err(e) => batch.errors.push(
+                    FeatureError {
+                        index,
+                        id,
+                        pointer: format!("/features/{index}{pointer}"),
+                        source: Box::new(e),
+                    }
+                    .into(),
+                ),
+            }
+        }
+        Ok(batch)
+    }
+
+    /// Returns the `id` of the feature, or the value of the identifying property.
+    fn identify(&self, feature: &JsonValue) -> Option<String> {
+        let text = |value: &JsonValue| match value {
+            JsonValue::String(s) => Some(s.clone()),
+            JsonValue::Number(n) => Some(n.to_string()),
+            _ => None,
+        };
+        if let Some(id) = feature.get("id").and_then(text) {
+            return Some(id);
+        }
+        let key = self.key.as_ref()?;
+        feature
+            .get("properties")
+            .and_then(|p| p.get(key))
+            .and_then(text)
+    }
+}
+
+/// Returns a JSON pointer, relative to the feature, to the first member that is not valid
+/// GeoJSON, or an empty pointer if no member is found.
+fn locate(feature: &JsonValue) -> String {
+    let Some(object) = feature.as_object() else {
+        return String::new();
+    };
+    if object.get("type").and_then(JsonValue::as_str) != Some("Feature") {
+        return "/type".to_string();
+    }
+    if let Some(id) = object.get("id") {
+        if !(id.is_string() || id.is_number()) {
+            return "/id".to_string();
+        }
+    }
+    if let Some(bbox) = object.get("bbox") {
+        if let Some(pointer) = locate_bbox(bbox) {
+            return pointer;
+        }
+    }
+    match object.get("properties") {
+        Some(JsonValue::Object(_)) | Some(JsonValue::Null) | None => {}
+        Some(_) => return "/properties".to_string(),
+    }
+    match object.get("geometry") {
+        Some(JsonValue::Null) | None => String::new(),
+        Some(geometry) => locate_geometry(geometry)
+            .map(|pointer| format!("/geometry{pointer}"))
+            .unwrap_or_default(),
+    }
+}
+
+/// Returns a pointer to the `bbox` member, or to its first non-numeric value.
+fn locate_bbox(bbox: &JsonValue) -> Option<String> {
+    let Some(values) = bbox.as_array() else {
+        return Some("/bbox".to_string());
+    };
+    values
+        .iter()
+        .position(|v| !v.is_number())
+        .map(|i| format!("/bbox/{i}"))
+}
+
+/// Returns a pointer, relative to `geometry`, to its first member that is not valid GeoJSON.
+fn locate_geometry(geometry: &JsonValue) -> Option<String> {
+    let Some(object) = geometry.as_object() else {
+        return Some(String::new());
+    };
+    if let Some(bbox) = object.get("bbox") {
+        if let Some(pointer) = locate_bbox(bbox) {
+            return Some(pointer);
+        }
+    }
+    let depth = match object.get("type").and_then(JsonValue::as_str) {
+        Some("Point") => 0,
+        Some("MultiPoint") | Some("LineString") => 1,
+        Some("MultiLineString") | Some("Polygon") => 2,
+        Some("MultiPolygon") => 3,
+        Some("GeometryCollection") => {
+            let Some(geometries) = object.get("geometries").and_then(JsonValue::as_array) else {
+                return Some("/geometries".to_string());
+            };
+            return geometries.iter().enumerate().find_map(|(i, geometry)| {
+                locate_geometry(geometry).map(|pointer| format!("/geometries/{i}{pointer}"))
+            });
+        }
+        _ => return Some("/type".to_string()),
+    };
+    match object.get("coordinates") {
+        Some(coordinates) => {
+            locate_coordinates(coordinates, depth).map(|pointer| format!("/coordinates{pointer}"))
+        }
+        None => Some("/coordinates".to_string()),
+    }
+}
+
+/// Returns a pointer, relative to `coordinates`, to its first invalid position, nested `depth`
+/// arrays deep.  Pointers are only built on the way out of a failure, so valid coordinates are
+/// walked without allocating.
+fn locate_coordinates(coordinates: &JsonValue, depth: usize) -> Option<String> {
+    let Some(values) = coordinates.as_array() else {
+        return Some(String::new());
+    };
+    if depth == 0 {
+        if values.len() < 2 {
+            return Some(String::new());
+        }
+        return values
+            .iter()
+            .position(|v| !v.is_number())
+            .map(|i| format!("/{i}"));
+    }
+    values
+        .iter()
+        .enumerate()
+        .find_map(|(i, v)| locate_coordinates(v, depth - 1).map(|pointer| format!("/{i}{pointer}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn errors(parser: &FeatureParser, features: JsonValue) -> Vec<(Option<String>, String)> {
+        let collection = json!({ "type": "FeatureCollection", "features": features });
+        parser
+            .parse_value(collection)
+            .unwrap()
+            .errors
+            .into_iter()
+            .map(|error| match error {
+                Bandage::GeoJsonFeature(e) => (e.id().map(str::to_string), e.pointer().to_string()),
+                other => panic!("expected a feature error, got {other:?}"),
+            })
+            .collect()
+    }
+
+    fn point(coordinates: JsonValue) -> JsonValue {
+        json!({ "type": "Point", "coordinates": coordinates })
+    }
+
+    #[test]
+    fn good_features_keep_their_index() {
+        let collection = json!({
+            "type": "FeatureCollection",
+            "features": [
+                { "type": "Feature", "geometry": point(json!([1.0, 2.0])), "properties": {} },
+                { "type": "Feature", "geometry": point(json!(["x", 2.0])), "properties": {} },
+                { "type": "Feature", "geometry": null, "properties": null },
+            ],
+        });
+        let batch = FeatureParser::new().parse_value(collection).unwrap();
+        let indexes: Vec<_> = batch.features.iter().map(|(i, _)| *i).collect();
+        assert_eq!(indexes, [0, 2]);
+        assert_eq!(batch.errors.len(), 1);
+    }
+
+    #[test]
+    fn features_are_identified_by_id_or_key() {
+        let bad = point(json!([1.0]));
+        let features = json!([
+            { "type": "Feature", "geometry": bad, "properties": {} },
+            { "type": "Feature", "id": 42, "geometry": bad, "properties": {} },
+            { "type": "Feature", "id": "parcel-7", "geometry": bad, "properties": {} },
+            { "type": "Feature", "geometry": bad, "properties": { "apn": "123-45" } },
+        ]);
+        let ids: Vec<_> = errors(&FeatureParser::new().key("apn"), features)
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        let expected = [None, Some("42"), Some("parcel-7"), Some("123-45")];
+        assert_eq!(ids, expected.map(|id| id.map(str::to_string)));
+    }
+
+    #[test]
+    fn pointers_reach_the_bad_member() {
+        let polygon = json!({
+            "type": "Polygon",
+            "coordinates": [[[0.0, 0.0], [1.0, 0.0], ["1", 1.0], [0.0, 0.0]]],
+        });
+        let collection = json!({
+            "type": "GeometryCollection",
+            "geometries": [point(json!([0.0, 0.0])), polygon],
+        });
+        let features = json!([
+            { "type": "Feature", "geometry": polygon, "properties": {} },
+            { "type": "Feature", "geometry": collection, "properties": {} },
+            { "type": "Feature", "geometry": point(json!([1.0])), "properties": {} },
+            { "type": "Feature", "geometry": { "type": "Circle" }, "properties": {} },
+            { "type": "Feature", "geometry": null, "properties": [] },
+            { "type": "Thing" },
+        ]);
+        let pointers: Vec<_> = errors(&FeatureParser::new(), features)
+            .into_iter()
+            .map(|(_, pointer)| pointer)
+            .collect();
+        assert_eq!(
+            pointers,
+            [
+                "/features/0/geometry/coordinates/0/2/0",
+                "/features/1/geometry/geometries/1/coordinates/0/2/0",
+                "/features/2/geometry/coordinates",
+                "/features/3/geometry/type",
+                "/features/4/properties",
+                "/features/5/type",
+            ]
+        );
+    }
+
+    #[test]
+    fn collections_fail_as_a_whole() {
+        let parser = FeatureParser::new();
+        assert!(matches!(parser.parse("not json"), Err(Bandage::GeoJson(_))));
+        assert!(matches!(
+            parser.parse(r#"{"type": "Feature"}"#),
+            Err(Bandage::GeoJson(_))
+        ));
+    }
+}
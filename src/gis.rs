@@ -0,0 +1,659 @@
+//! The `gis` module checks geometries read through `geojson` and `shapefile` before they reach
+//! downstream tools, reporting each problem as a [`Bandage::Geometry`] with the feature and the
+//! offending coordinates.
+//!
+//! ```
+//! use aid::prelude::*;
+//!
+//! let text = r#"{"type": "FeatureCollection", "features": [
+//!     {"type": "Feature", "id": "101", "properties": {},
+//!      "geometry": {"type": "Point", "coordinates": [-123.33, 42.44]}},
+//!     {"type": "Feature", "id": "102", "properties": {},
+//!      "geometry": {"type": "Point", "coordinates": [42.44, -123.33]}}
+//! ]}"#;
+//! let batch = FeatureParser::new().parse(text)?;
+//! let mut errors = batch.errors;
+//! let validator = GeometryValidator::new().bounds(BoundingBox::JOSEPHINE_COUNTY);
+//! for (_, feature) in &batch.features {
+//!     errors.extend(validator.feature(feature));
+//! }
+//! assert_eq!(errors.len(), 1);
+//! # Ok::<(), Bandage>(())
+//! ```
+use crate::prelude::Bandage;
+use std::fmt;
+
+/// The `Coordinate` type is an x (longitude or easting) and y (latitude or northing) pair.
+pub type Coordinate = [f64; 2];
+
+/// The `BoundingBox` struct is the extent that valid coordinates must fall within.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct BoundingBox {
+    min: Coordinate,
+    max: Coordinate,
+}
+
+impl BoundingBox {
+    /// The `JOSEPHINE_COUNTY` constant is the extent of Josephine County, Oregon, in WGS 84
+    /// longitude and latitude, with a small margin.
+    pub const JOSEPHINE_COUNTY: Self = Self {
+        min: [-124.05, 41.98],
+        max: [-123.22, 42.76],
+    };
+
+    /// The `new` method creates a bounding box from its corners, in either order.
+    pub fn new(a: Coordinate, b: Coordinate) -> Self {
+        Self {
+            min: [a[0].min(b[0]), a[1].min(b[1])],
+            max: [a[0].max(b[0]), a[1].max(b[1])],
+        }
+    }
+
+    /// The `min` method returns the corner with the least x and y.
+    pub fn min(&self) -> Coordinate {
+        self.min
+    }
+
+    /// The `max` method returns the corner with the greatest x and y.
+    pub fn max(&self) -> Coordinate {
+        self.max
+    }
+
+    /// The `contains` method returns `true` if `point` is inside or on the edge of the box.
+    pub fn contains(&self, point: Coordinate) -> bool {
+        (self.min[0]..=self.max[0]).contains(&point[0])
+            && (self.min[1]..=self.max[1]).contains(&point[1])
+    }
+}
+
+impl fmt::Display for BoundingBox {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "({}, {}) to ({}, {})",
+            self.min[0], self.min[1], self.max[0], self.max[1]
+        )
+    }
+}
+
+/// The `Winding` enum is the direction a ring is traced in.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Winding {
+    /// Clockwise, with y increasing northward.
+    Clockwise,
+    /// Counterclockwise, with y increasing northward.
+    Counterclockwise,
+}
+
+impl fmt::Display for Winding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Clockwise => write!(f, "clockwise"),
+            Self::Counterclockwise => write!(f, "counterclockwise"),
+        }
+    }
+}
+
+/// The `GeometryIssue` enum is the problem found with a geometry.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GeometryIssue {
+    /// The first and last points of a ring differ.
+    UnclosedRing,
+    /// A line or ring has too few points.
+    TooFewPoints {
+        /// The least number of points allowed.
+        required: usize,
+        /// The number of points found.
+        found: usize,
+    },
+    /// Two segments of a ring cross or overlap.
+    SelfIntersection,
+    /// A coordinate is NaN or infinite.
+    NonFinite,
+    /// A polygon ring is wound the wrong way for its format.
+    WindingOrder {
+        /// The winding the format requires for the ring.
+        expected: Winding,
+    },
+    /// A coordinate is outside the configured extent.
+    OutOfBounds {
+        /// The extent coordinates must fall within.
+        bounds: BoundingBox,
+    },
+}
+
+impl fmt::Display for GeometryIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnclosedRing => write!(f, "the ring is not closed"),
+            Self::TooFewPoints { required, found } => {
+                write!(f, "{found} points, but at least {required} are required")
+            }
+            Self::SelfIntersection => write!(f, "the ring intersects itself"),
+            Self::NonFinite => write!(f, "a coordinate is not a finite number"),
+            Self::WindingOrder { expected } => write!(f, "the ring should be {expected}"),
+            Self::OutOfBounds { bounds } => write!(f, "a coordinate is outside {bounds}"),
+        }
+    }
+}
+
+/// The `GeometryError` struct describes a problem with the geometry of a feature.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub struct GeometryError {
+    feature: Option<String>,
+    location: String,
+    issue: GeometryIssue,
+    coordinates: Vec<Coordinate>,
+}
+
+impl GeometryError {
+    /// The `feature` method returns the identifier of the feature, if known.
+    pub fn feature(&self) -> Option<&str> {
+        self.feature.as_deref()
+    }
+
+    /// The `location` method returns the part of the geometry with the problem, such as
+    /// `polygon 2 ring 0`.
+    pub fn location(&self) -> &str {
+        &self.location
+    }
+
+    /// The `issue` method returns the problem found.
+    pub fn issue(&self) -> &GeometryIssue {
+        &self.issue
+    }
+
+    /// The `coordinates` method returns the offending coordinates: the point outside the extent
+    /// or not finite, the first and last points of an unclosed ring, the endpoints of two
+    /// intersecting segments, or the points of a short line or wrongly wound ring.
+    pub fn coordinates(&self) -> &[Coordinate] {
+        &self.coordinates
+    }
+}
+
+impl fmt::Display for GeometryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(feature) = &self.feature {
+            write!(f, "feature {feature}, ")?;
+        }
+        write!(f, "{}: {} at", self.location, self.issue)?;
+        for (i, [x, y]) in self.coordinates.iter().take(4).enumerate() {
+            let sep = if i == 0 { " " } else { ", " };
+            write!(f, "{sep}({x}, {y})")?;
+        }
+        if self.coordinates.len() > 4 {
+            write!(f, ", ...")?;
+        }
+        Ok(())
+    }
+}
+
+/// The `Part` enum is a piece of a geometry, normalized from either format.
+enum Part {
+    Point(Coordinate),
+    Line(Vec<Coordinate>),
+    /// A polygon ring, with the winding its format requires, if the format fixes one.
+    Ring(Vec<Coordinate>, Option<Winding>),
+}
+
+/// The `GeometryValidator` struct checks geometries for problems.
+///
+/// By default it checks that coordinates are finite, that lines and rings have enough points
+/// and that rings are closed.  The extent, winding and self-intersection checks are opt-in.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct GeometryValidator {
+    bounds: Option<BoundingBox>,
+    winding: bool,
+    intersections: bool,
+}
+
+impl GeometryValidator {
+    /// The `new` method creates a validator with the default checks.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The `bounds` method sets the extent that coordinates must fall within.
+    pub fn bounds(mut self, bounds: BoundingBox) -> Self {
+        self.bounds = Some(bounds);
+        self
+    }
+
+    /// The `winding` method sets whether to check that GeoJSON exterior rings are
+    /// counterclockwise and holes clockwise, as RFC 7946 requires.  It is off by default, since
+    /// RFC 7946 tells parsers not to reject rings wound the other way.
+    ///
+    /// Shapefile rings are classified as exterior or hole by their winding when read, so they
+    /// cannot be wound the wrong way.
+    pub fn winding(mut self, check: bool) -> Self {
+        self.winding = check;
+        self
+    }
+
+    /// The `intersections` method sets whether to check rings for self-intersections.  It is off
+    /// by default, since it takes time proportional to the square of the number of points in
+    /// each ring.
+    pub fn intersections(mut self, check: bool) -> Self {
+        self.intersections = check;
+        self
+    }
+
+    /// The `feature` method checks the geometry of a GeoJSON feature, identified by its `id`.
+    pub fn feature(&self, feature: &geojson::Feature) -> Vec<Bandage> {
+        let id = feature.id.as_ref().map(|id| match id {
+            geojson::feature::Id::String(s) => s.clone(),
+            geojson::feature::Id::Number(n) => n.to_string(),
+        });
+        match &feature.geometry {
+            Some(geometry) => self.geojson(geometry, id.as_deref()),
+            None => Vec::new(),
+        }
+    }
+
+    /// The `geojson` method checks a GeoJSON geometry, naming `feature` in each error.
+    pub fn geojson(&self, geometry: &geojson::Geometry, feature: Option<&str>) -> Vec<Bandage> {
+        let mut parts = Vec::new();
+        collect_geojson(&geometry.value, "", &mut parts);
+        self.check(parts, feature)
+    }
+
+    /// The `shape` method checks a shapefile shape, naming `feature` in each error.
+    ///
+    /// Multipatch shapes are not checked.
+    pub fn shape(&self, shape: &shapefile::Shape, feature: Option<&str>) -> Vec<Bandage> {
+        use shapefile::Shape;
+        let parts = match shape {
+            Shape::NullShape | Shape::Multipatch(_) => Vec::new(),
+            Shape::Point(p) => vec![("point".to_string(), Part::Point([p.x, p.y]))],
+            Shape::PointM(p) => vec![("point".to_string(), Part::Point([p.x, p.y]))],
+            Shape::PointZ(p) => vec![("point".to_string(), Part::Point([p.x, p.y]))],
+            Shape::Multipoint(m) => points(m.points().iter().map(|p| [p.x, p.y])),
+            Shape::MultipointM(m) => points(m.points().iter().map(|p| [p.x, p.y])),
+            Shape::MultipointZ(m) => points(m.points().iter().map(|p| [p.x, p.y])),
+            Shape::Polyline(l) => lines(l.parts().iter().map(|p| xy(p, |p| [p.x, p.y]))),
+            Shape::PolylineM(l) => lines(l.parts().iter().map(|p| xy(p, |p| [p.x, p.y]))),
+            Shape::PolylineZ(l) => lines(l.parts().iter().map(|p| xy(p, |p| [p.x, p.y]))),
+            Shape::Polygon(p) => rings(p.rings().iter().map(|r| xy(r.points(), |p| [p.x, p.y]))),
+            Shape::PolygonM(p) => rings(p.rings().iter().map(|r| xy(r.points(), |p| [p.x, p.y]))),
+            Shape::PolygonZ(p) => rings(p.rings().iter().map(|r| xy(r.points(), |p| [p.x, p.y]))),
+        };
+        self.check(parts, feature)
+    }
+
+    /// The `record` method checks the shape of a record read by
+    /// [`crate::shape::ShapefileReader`], identified by its record index.
+    pub fn record(&self, record: &crate::shape::ShapeRecord) -> Vec<Bandage> {
+        let id = format!("record {}", record.index());
+        self.shape(record.shape(), Some(&id))
+    }
+
+    /// Checks each part, returning an error for each problem.
+    fn check(&self, parts: Vec<(String, Part)>, feature: Option<&str>) -> Vec<Bandage> {
+        let mut errors = Vec::new();
+        for (location, part) in parts {
+            let mut report = |issue, coordinates| {
+                errors.push(Bandage::from(GeometryError {
+                    feature: feature.map(str::to_string),
+                    location: location.clone(),
+                    issue,
+                    coordinates,
+                }))
+            };
+            let points = match &part {
+                Part::Point(point) => std::slice::from_ref(point),
+                // The closing point of a ring repeats the first, so check it once.
+                Part::Ring(points, _)
+                    if points.len() > 1 && points[0] == points[points.len() - 1] =>
+                {
+                    &points[..points.len() - 1]
+                }
+                Part::Line(points) | Part::Ring(points, _) => points.as_slice(),
+            };
+            let mut finite = true;
+            for &point in points {
+                if !(point[0].is_finite() && point[1].is_finite()) {
+                    finite = false;
+                    report(GeometryIssue::NonFinite, vec![point]);
+                } else if let Some(bounds) = self.bounds.filter(|b| !b.contains(point)) {
+                    report(GeometryIssue::OutOfBounds { bounds }, vec![point]);
+                }
+            }
+            match part {
+                Part::Point(_) => {}
+                Part::Line(points) => {
+                    if points.len() < 2 {
+                        report(
+                            GeometryIssue::TooFewPoints {
+                                required: 2,
+                                found: points.len(),
+                            },
+                            points,
+                        );
+                    }
+                }
+                Part::Ring(points, winding) => {
+                    if points.len() < 4 {
+                        report(
+                            GeometryIssue::TooFewPoints {
+                                required: 4,
+                                found: points.len(),
+                            },
+                            points,
+                        );
+                        continue;
+                    }
+                    let (first, last) = (points[0], points[points.len() - 1]);
+                    if first != last {
+                        report(GeometryIssue::UnclosedRing, vec![first, last]);
+                        continue;
+                    }
+                    if !finite {
+                        continue;
+                    }
+                    let expected = winding.filter(|_| self.winding);
+                    if let (Some(expected), Some(actual)) = (expected, winding_of(&points)) {
+                        if actual != expected {
+                            report(GeometryIssue::WindingOrder { expected }, points.clone());
+                        }
+                    }
+                    if self.intersections {
+                        if let Some(segments) = intersection(&points) {
+                            report(GeometryIssue::SelfIntersection, segments);
+                        }
+                    }
+                }
+            }
+        }
+        errors
+    }
+}
+
+/// Adds the parts of a GeoJSON geometry to `parts`, with locations under `prefix`.
+fn collect_geojson(value: &geojson::Value, prefix: &str, parts: &mut Vec<(String, Part)>) {
+    use geojson::Value;
+    let ring = |i: usize| {
+        if i == 0 {
+            Some(Winding::Counterclockwise)
+        } else {
+            Some(Winding::Clockwise)
+        }
+    };
+    match value {
+        Value::Point(p) => parts.push((format!("{prefix}point"), Part::Point(position(p)))),
+        Value::MultiPoint(ps) => {
+            for (i, p) in ps.iter().enumerate() {
+                parts.push((format!("{prefix}point {i}"), Part::Point(position(p))));
+            }
+        }
+        Value::LineString(line) => parts.push((
+            format!("{prefix}line"),
+            Part::Line(line.iter().map(position).collect()),
+        )),
+        Value::MultiLineString(lines) => {
+            for (i, line) in lines.iter().enumerate() {
+                parts.push((
+                    format!("{prefix}line {i}"),
+                    Part::Line(line.iter().map(position).collect()),
+                ));
+            }
+        }
+        Value::Polygon(polygon) => {
+            for (i, r) in polygon.iter().enumerate() {
+                parts.push((
+                    format!("{prefix}ring {i}"),
+                    Part::Ring(r.iter().map(position).collect(), ring(i)),
+                ));
+            }
+        }
+        Value::MultiPolygon(polygons) => {
+            for (j, polygon) in polygons.iter().enumerate() {
+                for (i, r) in polygon.iter().enumerate() {
+                    parts.push((
+                        format!("{prefix}polygon {j} ring {i}"),
+                        Part::Ring(r.iter().map(position).collect(), ring(i)),
+                    ));
+                }
+            }
+        }
+        Value::GeometryCollection(geometries) => {
+            for (i, geometry) in geometries.iter().enumerate() {
+                collect_geojson(&geometry.value, &format!("{prefix}geometry {i} "), parts);
+            }
+        }
+    }
+}
+
+/// Returns the x and y of a GeoJSON position, with NaN for a missing value so a short position
+/// built in code is reported rather than panicking.
+fn position(p: &geojson::Position) -> Coordinate {
+    let value = |i: usize| p.get(i).copied().unwrap_or(f64::NAN);
+    [value(0), value(1)]
+}
+
+/// Maps shapefile points to coordinates.
+fn xy<P>(points: &[P], f: impl Fn(&P) -> Coordinate) -> Vec<Coordinate> {
+    points.iter().map(f).collect()
+}
+
+fn points(points: impl Iterator<Item = Coordinate>) -> Vec<(String, Part)> {
+    points
+        .enumerate()
+        .map(|(i, p)| (format!("point {i}"), Part::Point(p)))
+        .collect()
+}
+
+fn lines(lines: impl Iterator<Item = Vec<Coordinate>>) -> Vec<(String, Part)> {
+    lines
+        .enumerate()
+        .map(|(i, l)| (format!("part {i}"), Part::Line(l)))
+        .collect()
+}
+
+fn rings(rings: impl Iterator<Item = Vec<Coordinate>>) -> Vec<(String, Part)> {
+    rings
+        .enumerate()
+        .map(|(i, r)| (format!("ring {i}"), Part::Ring(r, None)))
+        .collect()
+}
+
+/// Returns the winding of a closed ring from its signed area, or `None` if the area is zero.
+fn winding_of(ring: &[Coordinate]) -> Option<Winding> {
+    let area: f64 = ring
+        .windows(2)
+        .map(|w| w[0][0] * w[1][1] - w[1][0] * w[0][1])
+        .sum();
+    if area > 0.0 {
+        Some(Winding::Counterclockwise)
+    } else if area < 0.0 {
+        Some(Winding::Clockwise)
+    } else {
+        None
+    }
+}
+
+/// Returns the endpoints of the first two non-adjacent segments of a closed ring that touch.
+fn intersection(ring: &[Coordinate]) -> Option<Vec<Coordinate>> {
+    let segments: Vec<(Coordinate, Coordinate)> = ring
+        .windows(2)
+        .map(|w| (w[0], w[1]))
+        .filter(|(a, b)| a != b)
+        .collect();
+    let n = segments.len();
+    for i in 0..n {
+        // Skip the next segment, which shares an endpoint, and the first segment when `i` is
+        // the last, which shares the closing point.
+        for j in (i + 2)..n {
+            if i == 0 && j == n - 1 {
+                continue;
+            }
+            let (a, b) = segments[i];
+            let (c, d) = segments[j];
+            if touches(a, b, c, d) {
+                return Some(vec![a, b, c, d]);
+            }
+        }
+    }
+    None
+}
+
+/// Returns `true` if segment `ab` touches segment `cd`.
+fn touches(a: Coordinate, b: Coordinate, c: Coordinate, d: Coordinate) -> bool {
+    let cross = |o: Coordinate, p: Coordinate, q: Coordinate| {
+        (p[0] - o[0]) * (q[1] - o[1]) - (p[1] - o[1]) * (q[0] - o[0])
+    };
+    let within = |o: Coordinate, p: Coordinate, q: Coordinate| {
+        q[0] >= o[0].min(p[0])
+            && q[0] <= o[0].max(p[0])
+            && q[1] >= o[1].min(p[1])
+            && q[1] <= o[1].max(p[1])
+    };
+    let (d1, d2) = (cross(c, d, a), cross(c, d, b));
+    let (d3, d4) = (cross(a, b, c), cross(a, b, d));
+    if ((d1 > 0.0 && d2 < 0.0) || (d1 < 0.0 && d2 > 0.0))
+        && ((d3 > 0.0 && d4 < 0.0) || (d3 < 0.0 && d4 > 0.0))
+    {
+        return true;
+    }
+    (d1 == 0.0 && within(c, d, a))
+        || (d2 == 0.0 && within(c, d, b))
+        || (d3 == 0.0 && within(a, b, c))
+        || (d4 == 0.0 && within(a, b, d))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geojson::{Geometry, Value};
+
+    fn issues(validator: GeometryValidator, value: Value) -> Vec<(String, GeometryIssue)> {
+        validator
+            .geojson(&Geometry::new(value), Some("7"))
+            .into_iter()
+            .map(|error| match error {
+                Bandage::Geometry(e) => {
+                    assert_eq!(e.feature(), Some("7"));
+                    (e.location().to_string(), e.issue().clone())
+                }
+                other => panic!("expected a geometry error, got {other:?}"),
+            })
+            .collect()
+    }
+
+    fn ring(points: &[Coordinate]) -> Vec<Vec<f64>> {
+        points.iter().map(|p| p.to_vec()).collect()
+    }
+
+    const SQUARE: [Coordinate; 5] = [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0], [0.0, 0.0]];
+
+    #[test]
+    fn winding_follows_the_signed_area() {
+        assert_eq!(winding_of(&SQUARE), Some(Winding::Counterclockwise));
+        let mut clockwise = SQUARE;
+        clockwise.reverse();
+        assert_eq!(winding_of(&clockwise), Some(Winding::Clockwise));
+        assert_eq!(winding_of(&[[0.0, 0.0], [1.0, 1.0], [0.0, 0.0]]), None);
+    }
+
+    #[test]
+    fn winding_is_checked_when_asked() {
+        let mut clockwise = SQUARE;
+        clockwise.reverse();
+        let polygon = Value::Polygon(vec![ring(&clockwise)]);
+        assert!(issues(GeometryValidator::new(), polygon.clone()).is_empty());
+        assert_eq!(
+            issues(GeometryValidator::new().winding(true), polygon),
+            [(
+                "ring 0".to_string(),
+                GeometryIssue::WindingOrder {
+                    expected: Winding::Counterclockwise
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn bow_tie_intersects_itself() {
+        let bow_tie = [[0.0, 0.0], [1.0, 1.0], [1.0, 0.0], [0.0, 1.0], [0.0, 0.0]];
+        let polygon = Value::Polygon(vec![ring(&bow_tie)]);
+        assert!(issues(GeometryValidator::new(), polygon.clone()).is_empty());
+        let validator = GeometryValidator::new().intersections(true);
+        let errors = validator.geojson(&Geometry::new(polygon), None);
+        let [Bandage::Geometry(error)] = errors.as_slice() else {
+            panic!("expected one geometry error, got {errors:?}");
+        };
+        assert_eq!(error.issue(), &GeometryIssue::SelfIntersection);
+        assert_eq!(
+            error.coordinates(),
+            [[0.0, 0.0], [1.0, 1.0], [1.0, 0.0], [0.0, 1.0]]
+        );
+    }
+
+    #[test]
+    fn touching_rings_do_not_cross() {
+        // The hole touches the exterior ring at (1, 0), which each ring's own check ignores.
+        let exterior = [[0.0, 0.0], [2.0, 0.0], [2.0, 2.0], [0.0, 2.0], [0.0, 0.0]];
+        let hole = [[1.0, 0.0], [0.5, 1.0], [1.5, 1.0], [1.0, 0.0]];
+        let polygon = Value::Polygon(vec![ring(&exterior), ring(&hole)]);
+        let validator = GeometryValidator::new().winding(true).intersections(true);
+        assert!(issues(validator, polygon).is_empty());
+    }
+
+    #[test]
+    fn non_finite_coordinates() {
+        let line = Value::LineString(vec![vec![0.0, 0.0], vec![f64::NAN, 1.0], vec![2.0]]);
+        let bounds = BoundingBox::new([0.0, 0.0], [1.0, 1.0]);
+        assert_eq!(
+            issues(GeometryValidator::new().bounds(bounds), line),
+            [
+                ("line".to_string(), GeometryIssue::NonFinite),
+                ("line".to_string(), GeometryIssue::NonFinite),
+            ]
+        );
+    }
+
+    #[test]
+    fn rings_must_be_closed_and_long_enough() {
+        let open = [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]];
+        let short = [[0.0, 0.0], [1.0, 0.0], [0.0, 0.0]];
+        let polygon = Value::MultiPolygon(vec![vec![ring(&open)], vec![ring(&short)]]);
+        assert_eq!(
+            issues(GeometryValidator::new(), polygon),
+            [
+                ("polygon 0 ring 0".to_string(), GeometryIssue::UnclosedRing),
+                (
+                    "polygon 1 ring 0".to_string(),
+                    GeometryIssue::TooFewPoints {
+                        required: 4,
+                        found: 3
+                    }
+                ),
+            ]
+        );
+        let line = Value::LineString(vec![vec![0.0, 0.0]]);
+        assert_eq!(
+            issues(GeometryValidator::new(), line),
+            [(
+                "line".to_string(),
+                GeometryIssue::TooFewPoints {
+                    required: 2,
+                    found: 1
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn out_of_bounds_points_are_reported() {
+        let points = Value::MultiPoint(vec![vec![-123.33, 42.44], vec![42.44, -123.33]]);
+        let validator = GeometryValidator::new().bounds(BoundingBox::JOSEPHINE_COUNTY);
+        assert_eq!(
+            issues(validator, points),
+            [(
+                "point 1".to_string(),
+                GeometryIssue::OutOfBounds {
+                    bounds: BoundingBox::JOSEPHINE_COUNTY
+                }
+            )]
+        );
+    }
+}
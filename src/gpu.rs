@@ -0,0 +1,233 @@
+//! The `gpu` module converts the `wgpu` failures that do not arrive as `Result` errors: a missing
+//! adapter, which `wgpu` reports as `None`, and uncaptured device errors, which `wgpu` passes to a
+//! callback.
+//!
+//! Both [`NoAdapterError`] and [`UncapturedError`] can be built from plain values, so the
+//! conversions can be exercised without a GPU.
+use crate::prelude::{Bandage, Clean};
+use std::sync::mpsc;
+
+/// The `NoAdapterError` struct records the options of a request that found no GPU adapter.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error(
+    "No GPU adapter matched the request (power preference {power_preference:?}, fallback adapter {force_fallback_adapter}, compatible surface {compatible_surface})"
+)]
+pub struct NoAdapterError {
+    power_preference: wgpu::PowerPreference,
+    force_fallback_adapter: bool,
+    compatible_surface: bool,
+}
+
+impl NoAdapterError {
+    /// The `new` method records the options of the failed request.
+    pub fn new(options: &wgpu::RequestAdapterOptions<'_, '_>) -> Self {
+        Self {
+            power_preference: options.power_preference,
+            force_fallback_adapter: options.force_fallback_adapter,
+            compatible_surface: options.compatible_surface.is_some(),
+        }
+    }
+
+    /// The `power_preference` method returns the requested power preference.
+    pub fn power_preference(&self) -> wgpu::PowerPreference {
+        self.power_preference
+    }
+
+    /// The `force_fallback_adapter` method returns whether only a fallback adapter was requested.
+    pub fn force_fallback_adapter(&self) -> bool {
+        self.force_fallback_adapter
+    }
+
+    /// The `compatible_surface` method returns whether the adapter had to present to a surface.
+    pub fn compatible_surface(&self) -> bool {
+        self.compatible_surface
+    }
+}
+
+/// The `request_adapter` function requests an adapter from `instance`, returning a
+/// [`Bandage::WgpuAdapter`] instead of `None` if no adapter matches `options`.
+pub async fn request_adapter(
+    instance: &wgpu::Instance,
+    options: &wgpu::RequestAdapterOptions<'_, '_>,
+) -> Clean<wgpu::Adapter> {
+    instance
+        .request_adapter(options)
+        .await
+        .ok_or_else(|| NoAdapterError::new(options).into())
+}
+
+/// The `UncapturedKind` enum is the kind of an uncaptured `wgpu` error.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum UncapturedKind {
+    /// The device ran out of memory.
+    OutOfMemory,
+    /// A call failed validation, signifying a bug in the code or data.
+    Validation,
+}
+
+/// The `UncapturedError` struct holds the messages of a [`wgpu::Error`], which is not `Sync`
+/// and so cannot be held by a [`Bandage`] directly.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{kind:?}: {description}")]
+pub struct UncapturedError {
+    kind: UncapturedKind,
+    description: String,
+}
+
+impl UncapturedError {
+    /// The `kind` method returns whether the device ran out of memory or a call failed
+    /// validation.
+    pub fn kind(&self) -> UncapturedKind {
+        self.kind
+    }
+
+    /// The `description` method returns the description of the error from `wgpu`.
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+}
+
+impl From<wgpu::Error> for UncapturedError {
+    fn from(error: wgpu::Error) -> Self {
+        match error {
+            wgpu::Error::OutOfMemory { source } => Self {
+                kind: UncapturedKind::OutOfMemory,
+                description: source.to_string(),
+            },
+            wgpu::Error::Validation {
+                source,
+                description,
+            } => {
+                let source = source.to_string();
+                let description = if description.is_empty() || description == source {
+                    source
+                } else {
+                    format!("{description} ({source})")
+                };
+                Self {
+                    kind: UncapturedKind::Validation,
+                    description,
+                }
+            }
+        }
+    }
+}
+
+/// The `uncaptured_handler` function returns a handler for
+/// [`wgpu::Device::on_uncaptured_error`] that sends each error to `sender`.
+///
+/// Errors are dropped once the receiver is gone.
+pub fn uncaptured_handler(sender: mpsc::Sender<Bandage>) -> impl Fn(wgpu::Error) + Send + 'static {
+    move |error| {
+        let _ = sender.send(error.into());
+    }
+}
+
+/// The `forward_uncaptured` function sends the uncaptured errors of `device` to the returned
+/// receiver, replacing the default handler, which panics.
+pub fn forward_uncaptured(device: &wgpu::Device) -> mpsc::Receiver<Bandage> {
+    let (sender, receiver) = mpsc::channel();
+    device.on_uncaptured_error(Box::new(uncaptured_handler(sender)));
+    receiver
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{BandageKind, MessageId};
+
+    fn display_message() -> String {
+        MessageId::Display
+            .template(crate::prelude::Locale::English)
+            .to_string()
+    }
+
+    #[test]
+    fn no_adapter_records_options() {
+        let options = wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::HighPerformance,
+            force_fallback_adapter: true,
+            compatible_surface: None,
+        };
+        let error = NoAdapterError::new(&options);
+        assert_eq!(
+            error.power_preference(),
+            wgpu::PowerPreference::HighPerformance
+        );
+        assert!(error.force_fallback_adapter());
+        assert!(!error.compatible_surface());
+        let bandage = Bandage::from(error);
+        assert_eq!(bandage.kind(), BandageKind::WgpuAdapter);
+        assert!(!bandage.is_retryable());
+        assert_eq!(bandage.user_message(), display_message());
+        assert!(bandage.to_string().contains("HighPerformance"));
+    }
+
+    #[test]
+    fn uncaptured_out_of_memory() {
+        let error = wgpu::Error::OutOfMemory {
+            source: Box::new(std::io::Error::other("heap exhausted")),
+        };
+        let uncaptured = UncapturedError::from(error);
+        assert_eq!(uncaptured.kind(), UncapturedKind::OutOfMemory);
+        assert_eq!(uncaptured.description(), "heap exhausted");
+        let bandage = Bandage::from(uncaptured);
+        assert_eq!(bandage.kind(), BandageKind::WgpuUncaptured);
+        assert!(!bandage.is_retryable());
+        assert_eq!(bandage.user_message(), display_message());
+    }
+
+    #[test]
+    fn uncaptured_validation() {
+        let error = wgpu::Error::Validation {
+            source: Box::new(std::io::Error::other("buffer is destroyed")),
+            description: "In Queue::write_buffer".to_string(),
+        };
+        let bandage = Bandage::from(error);
+        assert_eq!(bandage.kind(), BandageKind::WgpuUncaptured);
+        assert!(!bandage.is_retryable());
+        assert_eq!(bandage.user_message(), display_message());
+        let Bandage::WgpuUncaptured(uncaptured) = bandage else {
+            unreachable!()
+        };
+        assert_eq!(uncaptured.kind(), UncapturedKind::Validation);
+        assert_eq!(
+            uncaptured.description(),
+            "In Queue::write_buffer (buffer is destroyed)"
+        );
+    }
+
+    #[test]
+    fn uncaptured_handler_forwards() {
+        let (sender, receiver) = mpsc::channel();
+        let handler = uncaptured_handler(sender);
+        handler(wgpu::Error::OutOfMemory {
+            source: Box::new(std::io::Error::other("heap exhausted")),
+        });
+        let bandage = receiver.try_recv().unwrap();
+        assert_eq!(bandage.kind(), BandageKind::WgpuUncaptured);
+    }
+
+    #[test]
+    fn surface_errors() {
+        for (error, retryable) in [
+            (wgpu::SurfaceError::Timeout, true),
+            (wgpu::SurfaceError::Outdated, true),
+            (wgpu::SurfaceError::Lost, true),
+            (wgpu::SurfaceError::OutOfMemory, false),
+        ] {
+            let bandage = Bandage::from(error);
+            assert_eq!(bandage.kind(), BandageKind::WgpuFrame);
+            assert_eq!(bandage.is_retryable(), retryable);
+            assert_eq!(bandage.user_message(), display_message());
+        }
+    }
+
+    #[test]
+    fn buffer_async_error() {
+        let bandage = Bandage::from(wgpu::BufferAsyncError);
+        assert_eq!(bandage.kind(), BandageKind::WgpuBuffer);
+        assert!(!bandage.is_retryable());
+        assert_eq!(bandage.user_message(), display_message());
+    }
+}
@@ -0,0 +1,342 @@
+//! The `icon` module loads window icons from image files or bytes, for the Dioxus desktop app
+//! with the `icon` feature and for `winit` windows with the `win` feature.
+//!
+//! Failures return a [`Bandage::Icon`] naming the file, the detected image format and the step
+//! that failed, rather than a bare `BadIcon`.
+//!
+//! ```no_run
+//! # #[cfg(feature = "win")]
+//! # fn main() -> aid::prelude::Clean<()> {
+//! let icon: winit::window::Icon = aid::icon::load("assets/icon.png")?;
+//! # Ok(())
+//! # }
+//! # #[cfg(not(feature = "win"))]
+//! # fn main() {}
+//! ```
+use crate::prelude::Clean;
+use std::path::{Path, PathBuf};
+
+/// The `DEFAULT_MAX_SIZE` constant is the largest icon width or height accepted by default, in
+/// pixels.
+pub const DEFAULT_MAX_SIZE: u32 = 1024;
+
+/// The `IconSource` enum is the file or bytes an icon is loaded from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IconSource {
+    /// An image file on disk.
+    Path(PathBuf),
+    /// An encoded image in memory, such as from `include_bytes!`.
+    Bytes(Vec<u8>),
+}
+
+impl std::fmt::Display for IconSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Path(path) => write!(f, "{}", path.display()),
+            Self::Bytes(bytes) => write!(f, "<{} bytes>", bytes.len()),
+        }
+    }
+}
+
+impl From<&Path> for IconSource {
+    fn from(path: &Path) -> Self {
+        Self::Path(path.to_path_buf())
+    }
+}
+
+impl From<PathBuf> for IconSource {
+    fn from(path: PathBuf) -> Self {
+        Self::Path(path)
+    }
+}
+
+impl From<&str> for IconSource {
+    fn from(path: &str) -> Self {
+        Self::Path(PathBuf::from(path))
+    }
+}
+
+impl From<String> for IconSource {
+    fn from(path: String) -> Self {
+        Self::Path(PathBuf::from(path))
+    }
+}
+
+impl From<&[u8]> for IconSource {
+    fn from(bytes: &[u8]) -> Self {
+        Self::Bytes(bytes.to_vec())
+    }
+}
+
+impl<const N: usize> From<&[u8; N]> for IconSource {
+    fn from(bytes: &[u8; N]) -> Self {
+        Self::Bytes(bytes.to_vec())
+    }
+}
+
+impl From<Vec<u8>> for IconSource {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self::Bytes(bytes)
+    }
+}
+
+/// The `IconStage` enum is the step of loading an icon that failed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum IconStage {
+    /// Reading the file.
+    Read,
+    /// Detecting the image format from the contents or the file extension.
+    Detect,
+    /// Decoding the image.
+    Decode,
+    /// Checking the width and height.
+    Dimensions,
+    /// Creating the icon from the RGBA pixels.
+    Convert,
+}
+
+impl std::fmt::Display for IconStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let stage = match self {
+            Self::Read => "reading the file",
+            Self::Detect => "detecting the image format",
+            Self::Decode => "decoding the image",
+            Self::Dimensions => "checking the dimensions",
+            Self::Convert => "creating the icon",
+        };
+        write!(f, "{stage}")
+    }
+}
+
+/// The `IconError` struct describes a failure to load an icon.
+#[derive(Debug, thiserror::Error)]
+pub struct IconError {
+    source_name: String,
+    format: Option<image::ImageFormat>,
+    stage: IconStage,
+    detail: String,
+    #[source]
+    source: Option<Box<dyn std::error::Error + Send + Sync>>,
+}
+
+impl IconError {
+    fn new(source: &IconSource, format: Option<image::ImageFormat>, stage: IconStage) -> Self {
+        Self {
+            source_name: source.to_string(),
+            format,
+            stage,
+            detail: String::new(),
+            source: None,
+        }
+    }
+
+    fn detail<S: Into<String>>(mut self, detail: S) -> Self {
+        self.detail = detail.into();
+        self
+    }
+
+    fn with_source<E: std::error::Error + Send + Sync + 'static>(mut self, source: E) -> Self {
+        self.detail = source.to_string();
+        self.source = Some(Box::new(source));
+        self
+    }
+
+    /// The `source_name` method returns the path of the file, or the length of the bytes.
+    pub fn source_name(&self) -> &str {
+        &self.source_name
+    }
+
+    /// The `format` method returns the detected image format, if detection succeeded.
+    pub fn format(&self) -> Option<image::ImageFormat> {
+        self.format
+    }
+
+    /// The `stage` method returns the step that failed.
+    pub fn stage(&self) -> IconStage {
+        self.stage
+    }
+}
+
+impl std::fmt::Display for IconError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.source_name)?;
+        if let Some(format) = self.format {
+            write!(f, " ({format:?})")?;
+        }
+        write!(f, " failed while {}: {}", self.stage, self.detail)
+    }
+}
+
+/// The `IconTarget` trait is an icon type that [`IconLoader`] can create from RGBA pixels.
+pub trait IconTarget: Sized {
+    /// The `from_rgba` method creates the icon from `width * height` RGBA pixels.
+    fn from_rgba(rgba: Vec<u8>, width: u32, height: u32) -> Clean<Self>;
+}
+
+#[cfg(feature = "icon")]
+#[cfg_attr(docsrs, doc(cfg(feature = "icon")))]
+impl IconTarget for dioxus_desktop::tao::window::Icon {
+    fn from_rgba(rgba: Vec<u8>, width: u32, height: u32) -> Clean<Self> {
+        Ok(Self::from_rgba(rgba, width, height)?)
+    }
+}
+
+#[cfg(feature = "win")]
+#[cfg_attr(docsrs, doc(cfg(feature = "win")))]
+impl IconTarget for winit::window::Icon {
+    fn from_rgba(rgba: Vec<u8>, width: u32, height: u32) -> Clean<Self> {
+        Ok(Self::from_rgba(rgba, width, height)?)
+    }
+}
+
+/// The `IconLoader` struct decodes an image into an icon, checking its dimensions.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct IconLoader {
+    max_size: u32,
+    square: bool,
+    size: Option<(u32, u32)>,
+}
+
+impl Default for IconLoader {
+    fn default() -> Self {
+        Self {
+            max_size: DEFAULT_MAX_SIZE,
+            square: true,
+            size: None,
+        }
+    }
+}
+
+impl IconLoader {
+    /// The `new` method creates a loader that accepts square images up to
+    /// [`DEFAULT_MAX_SIZE`] pixels wide.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The `max_size` method sets the largest width or height accepted, in pixels.
+    pub fn max_size(mut self, max_size: u32) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// The `square` method sets whether the width and height must match.
+    pub fn square(mut self, square: bool) -> Self {
+        self.square = square;
+        self
+    }
+
+    /// The `size` method requires the image to be exactly `width` by `height` pixels.
+    pub fn size(mut self, width: u32, height: u32) -> Self {
+        self.size = Some((width, height));
+        self
+    }
+
+    /// The `load` method reads and decodes `source` into an icon of type `I`.
+    pub fn load<I: IconTarget, S: Into<IconSource>>(&self, source: S) -> Clean<I> {
+        let source = source.into();
+        let bytes = match &source {
+            IconSource::Path(path) => std::fs::read(path)
+                .map_err(|e| IconError::new(&source, None, IconStage::Read).with_source(e))?,
+            IconSource::Bytes(bytes) => bytes.clone(),
+        };
+        let mut reader = image::ImageReader::new(std::io::Cursor::new(bytes))
+            .with_guessed_format()
+            .map_err(|e| IconError::new(&source, None, IconStage::Detect).with_source(e))?;
+        let format = match (reader.format(), &source) {
+            (Some(format), _) => format,
+            (None, IconSource::Path(path)) => image::ImageFormat::from_path(path)
+                .map_err(|e| IconError::new(&source, None, IconStage::Detect).with_source(e))?,
+            (None, IconSource::Bytes(_)) => {
+                return Err(IconError::new(&source, None, IconStage::Detect)
+                    .detail("the contents do not match a known image format")
+                    .into());
+            }
+        };
+        reader.set_format(format);
+        let image = reader
+            .decode()
+            .map_err(|e| IconError::new(&source, Some(format), IconStage::Decode).with_source(e))?;
+        let (width, height) = (image.width(), image.height());
+        if let Some(detail) = self.check(width, height) {
+            return Err(IconError::new(&source, Some(format), IconStage::Dimensions)
+                .detail(detail)
+                .into());
+        }
+        I::from_rgba(image.into_rgba8().into_raw(), width, height).map_err(|e| {
+            IconError::new(&source, Some(format), IconStage::Convert)
+                .with_source(e)
+                .into()
+        })
+    }
+
+    /// Returns why `width` by `height` is not accepted, if it is not.
+    fn check(&self, width: u32, height: u32) -> Option<String> {
+        if width == 0 || height == 0 {
+            return Some(format!("the image is {width}x{height} pixels"));
+        }
+        if let Some((w, h)) = self.size {
+            if (width, height) != (w, h) {
+                return Some(format!(
+                    "the image is {width}x{height} pixels, but {w}x{h} is required"
+                ));
+            }
+        }
+        if self.square && width != height {
+            return Some(format!(
+                "the image is {width}x{height} pixels, but icons must be square"
+            ));
+        }
+        if width > self.max_size || height > self.max_size {
+            return Some(format!(
+                "the image is {width}x{height} pixels, larger than the limit of {}",
+                self.max_size
+            ));
+        }
+        None
+    }
+}
+
+/// The `load` function reads and decodes an icon from a path or bytes with the default
+/// [`IconLoader`].
+pub fn load<I: IconTarget, S: Into<IconSource>>(source: S) -> Clean<I> {
+    IconLoader::new().load(source)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::Bandage;
+
+    #[derive(Debug)]
+    struct Pixels(u32, u32);
+
+    impl IconTarget for Pixels {
+        fn from_rgba(_rgba: Vec<u8>, width: u32, height: u32) -> Clean<Self> {
+            Ok(Self(width, height))
+        }
+    }
+
+    #[test]
+    fn format_from_extension_is_used_to_decode() {
+        let dir = std::env::temp_dir().join(format!("aid-icon-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        // TGA files have no magic number, so only the extension names the format.
+        let path = dir.join("icon.tga");
+        image::RgbaImage::new(4, 4).save(&path).unwrap();
+        let icon: Pixels = IconLoader::new().load(path.as_path()).unwrap();
+        assert_eq!((icon.0, icon.1), (4, 4));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn unknown_bytes_fail_detection() {
+        let error = IconLoader::new()
+            .load::<Pixels, _>(&[0u8, 1, 2, 3])
+            .unwrap_err();
+        let Bandage::Icon(error) = error else {
+            panic!("expected an icon error, got {error:?}");
+        };
+        assert_eq!(error.stage(), IconStage::Detect);
+    }
+}
@@ -0,0 +1,288 @@
+//! The `layer` module exports the errors from a spatial import as GeoJSON, so GIS analysts can
+//! open them in QGIS or ArcGIS Pro next to the data.
+//!
+//! Errors with coordinates, such as a [`Bandage::Geometry`], become point features. Errors
+//! without a location go in a separate collection with no geometries, which GIS software opens
+//! as a table.
+//!
+//! ```no_run
+//! use aid::prelude::*;
+//!
+//! # let errors: Vec<Bandage> = Vec::new();
+//! let layer = ErrorLayer::new().source_file("parcels.geojson").extend(&errors);
+//! layer.write("parcel_errors.geojson", "parcel_errors_table.geojson")?;
+//! # Ok::<(), Bandage>(())
+//! ```
+use crate::prelude::{Bandage, Clean};
+use geojson::{Feature, FeatureCollection, Geometry, JsonObject, JsonValue, Value};
+use std::path::Path;
+
+/// The `ErrorLayer` struct collects errors as GeoJSON features.
+#[derive(Debug, Clone, Default)]
+pub struct ErrorLayer {
+    source_file: Option<String>,
+    located: Vec<Feature>,
+    unlocated: Vec<Feature>,
+}
+
+impl ErrorLayer {
+    /// The `new` method creates an empty layer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The `source_file` method sets the file recorded for errors that do not name their own,
+    /// such as geometry errors from a GeoJSON upload.
+    pub fn source_file<S: Into<String>>(mut self, file: S) -> Self {
+        self.source_file = Some(file.into());
+        self
+    }
+
+    /// The `extend` method adds each error in `errors` to the layer.
+    pub fn extend<'a, I: IntoIterator<Item = &'a Bandage>>(mut self, errors: I) -> Self {
+        for error in errors {
+            self.push(error);
+        }
+        self
+    }
+
+    /// The `push` method adds `error` to the layer, as a point feature if it has coordinates or
+    /// to the table otherwise, adding each error of a [`Bandage::Aggregate`] on its own.
+    pub fn push(&mut self, error: &Bandage) {
+        if let Bandage::Aggregate(errors) = error {
+            for error in errors.errors() {
+                self.push(error);
+            }
+            return;
+        }
+        let mut properties = JsonObject::new();
+        properties.insert("code".to_string(), error.code().into());
+        properties.insert("message".to_string(), error.to_string().into());
+        let mut file = self.source_file.clone();
+        let mut feature = None;
+        let mut location = None;
+        let mut coordinates = Vec::new();
+        match error {
+            Bandage::Geometry(e) => {
+                feature = e.feature().map(str::to_string);
+                location = Some(e.location().to_string());
+                coordinates = e
+                    .coordinates()
+                    .iter()
+                    .filter(|[x, y]| x.is_finite() && y.is_finite())
+                    .map(|[x, y]| vec![*x, *y])
+                    .collect();
+            }
+            Bandage::GeoJsonFeature(e) => {
+                feature = Some(e.id().map_or_else(|| e.index().to_string(), str::to_string));
+                location = Some(e.pointer().to_string());
+            }
+            Bandage::ShapefileRecord(e) => {
+                file = Some(e.path().display().to_string());
+                feature = e.record_index().map(|i| format!("record {i}"));
+                location = e.field().map(|f| format!("field '{f}'"));
+            }
+            _ => {}
+        }
+        properties.insert("file".to_string(), option(file));
+        properties.insert("feature".to_string(), option(feature));
+        properties.insert("location".to_string(), option(location));
+        let geometry = match coordinates.len() {
+            0 => None,
+            1 => Some(Geometry::new(Value::Point(coordinates.remove(0)))),
+            _ => Some(Geometry::new(Value::MultiPoint(coordinates))),
+        };
+        let target = if geometry.is_some() {
+            &mut self.located
+        } else {
+            &mut self.unlocated
+        };
+        target.push(Feature {
+            bbox: None,
+            geometry,
+            id: None,
+            properties: Some(properties),
+            foreign_members: None,
+        });
+    }
+
+    /// The `len` method returns the number of errors in the layer.
+    pub fn len(&self) -> usize {
+        self.located.len() + self.unlocated.len()
+    }
+
+    /// The `is_empty` method returns `true` if the layer holds no errors.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The `features` method returns the errors with coordinates as a `FeatureCollection` of
+    /// points.
+    pub fn features(&self) -> FeatureCollection {
+        collection(&self.located)
+    }
+
+    /// The `table` method returns the errors without coordinates as a `FeatureCollection` with
+    /// no geometries.
+    pub fn table(&self) -> FeatureCollection {
+        collection(&self.unlocated)
+    }
+
+    /// The `write` method writes the located errors to `features` and the other errors to
+    /// `table`, as GeoJSON.
+    pub fn write<P: AsRef<Path>, Q: AsRef<Path>>(&self, features: P, table: Q) -> Clean<()> {
+        std::fs::write(features, self.features().to_string())?;
+        std::fs::write(table, self.table().to_string())?;
+        Ok(())
+    }
+}
+
+fn option(value: Option<String>) -> JsonValue {
+    value.map_or(JsonValue::Null, JsonValue::from)
+}
+
+fn collection(features: &[Feature]) -> FeatureCollection {
+    FeatureCollection {
+        bbox: None,
+        features: features.to_vec(),
+        foreign_members: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gis::{BoundingBox, GeometryValidator};
+    use crate::prelude::AggregateError;
+    use crate::shape::ShapefileError;
+
+    fn property<'a>(feature: &'a Feature, name: &str) -> &'a JsonValue {
+        &feature.properties.as_ref().unwrap()[name]
+    }
+
+    fn geometry(errors: Vec<Bandage>) -> ErrorLayer {
+        ErrorLayer::new()
+            .source_file("parcels.geojson")
+            .extend(&errors)
+    }
+
+    #[test]
+    fn located_and_unlocated_errors_are_split() {
+        let bounds = BoundingBox::new([0.0, 0.0], [1.0, 1.0]);
+        let validator = GeometryValidator::new().bounds(bounds);
+        let infinite = Geometry::new(Value::Point(vec![f64::INFINITY, 1.0]));
+        let outside = Geometry::new(Value::Point(vec![5.0, 5.0]));
+        let mut layer = ErrorLayer::new()
+            .source_file("parcels.geojson")
+            .extend(&[Bandage::Hint("no owner".to_string())])
+            .extend(&validator.geojson(&infinite, Some("9")));
+        layer.push(&validator.geojson(&outside, Some("10"))[0]);
+        assert_eq!(layer.len(), 3);
+        let features = layer.features().features;
+        let table = layer.table().features;
+        assert_eq!(features.len(), 1);
+        assert_eq!(table.len(), 2);
+        assert_eq!(property(&features[0], "feature"), "10");
+        assert_eq!(property(&features[0], "file"), "parcels.geojson");
+        assert!(table.iter().all(|feature| feature.geometry.is_none()));
+        assert_eq!(property(&table[0], "code"), "hint");
+        assert_eq!(property(&table[0], "location"), &JsonValue::Null);
+    }
+
+    #[test]
+    fn aggregates_are_flattened() {
+        let aggregate = AggregateError::new(vec![
+            Bandage::Hint("first".to_string()),
+            Bandage::Hint("second".to_string()),
+        ]);
+        let layer = ErrorLayer::new().extend(&[aggregate.into()]);
+        assert_eq!(layer.len(), 2);
+        let messages: Vec<_> = layer
+            .table()
+            .features
+            .iter()
+            .map(|feature| property(feature, "message").clone())
+            .collect();
+        assert_eq!(messages, ["Hint: first", "Hint: second"]);
+    }
+
+    #[test]
+    fn coordinates_become_points() {
+        let bounds = BoundingBox::new([0.0, 0.0], [1.0, 1.0]);
+        let point = GeometryValidator::new()
+            .bounds(bounds)
+            .geojson(&Geometry::new(Value::Point(vec![5.0, 5.0])), None);
+        let ring = vec![
+            vec![0.0, 0.0],
+            vec![1.0, 0.0],
+            vec![1.0, 1.0],
+            vec![0.0, 1.0],
+        ];
+        let unclosed =
+            GeometryValidator::new().geojson(&Geometry::new(Value::Polygon(vec![ring])), None);
+        let features = geometry(point).extend(&unclosed).features().features;
+        let values: Vec<_> = features
+            .iter()
+            .map(|feature| feature.geometry.as_ref().unwrap().value.clone())
+            .collect();
+        assert_eq!(
+            values,
+            [
+                Value::Point(vec![5.0, 5.0]),
+                Value::MultiPoint(vec![vec![0.0, 0.0], vec![0.0, 1.0]]),
+            ]
+        );
+    }
+
+    #[test]
+    fn non_finite_coordinates_are_dropped() {
+        // The ring is too short, and its second point is not a number.
+        let ring = vec![vec![0.0, 0.0], vec![f64::NAN, 1.0], vec![0.0, 0.0]];
+        let errors =
+            GeometryValidator::new().geojson(&Geometry::new(Value::Polygon(vec![ring])), None);
+        let layer = geometry(errors);
+        let features = layer.features().features;
+        assert_eq!(features.len(), 1);
+        assert_eq!(
+            features[0].geometry.as_ref().unwrap().value,
+            Value::MultiPoint(vec![vec![0.0, 0.0], vec![0.0, 0.0]])
+        );
+        let table = layer.table().features;
+        assert_eq!(table.len(), 1);
+        assert_eq!(property(&table[0], "code"), "geometry");
+    }
+
+    #[test]
+    fn shapefile_errors_name_their_own_file() {
+        let error = ShapefileError::new("parcels.shp", shapefile::Error::MissingDbf).record(3);
+        let layer = ErrorLayer::new()
+            .source_file("upload.zip")
+            .extend(&[error.into(), Bandage::Hint("no owner".to_string())]);
+        let table = layer.table().features;
+        assert_eq!(property(&table[0], "file"), "parcels.dbf");
+        assert_eq!(property(&table[0], "feature"), "record 3");
+        assert_eq!(property(&table[1], "file"), "upload.zip");
+    }
+
+    #[test]
+    fn write_creates_both_files() {
+        let dir = std::env::temp_dir().join(format!("aid-layer-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (features, table) = (dir.join("errors.geojson"), dir.join("table.geojson"));
+        ErrorLayer::new()
+            .extend(&[Bandage::Hint("no owner".to_string())])
+            .write(&features, &table)
+            .unwrap();
+        let table: FeatureCollection = std::fs::read_to_string(&table)
+            .unwrap()
+            .parse::<geojson::GeoJson>()
+            .unwrap()
+            .try_into()
+            .unwrap();
+        assert_eq!(table.features.len(), 1);
+        assert!(std::fs::read_to_string(&features)
+            .unwrap()
+            .contains("FeatureCollection"));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -3,10 +3,104 @@
 //! An error-handling library for bubbling up errors in library code.
 
 pub mod aid;
+pub mod auth;
+pub mod collect;
+#[cfg(all(feature = "env", feature = "serial"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "env", feature = "serial"))))]
+pub mod config;
+#[cfg(feature = "serial")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serial")))]
+pub mod crash;
+#[cfg(feature = "env")]
+#[cfg_attr(docsrs, doc(cfg(feature = "env")))]
+pub mod env;
 /// The `error` module holds aliases for `Result` and `Error`.
 mod error;
+#[cfg(feature = "gis")]
+#[cfg_attr(docsrs, doc(cfg(feature = "gis")))]
+pub mod features;
+#[cfg(feature = "gis")]
+#[cfg_attr(docsrs, doc(cfg(feature = "gis")))]
+pub mod gis;
+#[cfg(feature = "gpu")]
+#[cfg_attr(docsrs, doc(cfg(feature = "gpu")))]
+pub mod gpu;
+#[cfg(any(feature = "icon", feature = "win"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "icon", feature = "win"))))]
+pub mod icon;
+#[cfg(feature = "gis")]
+#[cfg_attr(docsrs, doc(cfg(feature = "gis")))]
+pub mod layer;
+#[cfg(feature = "gis")]
+#[cfg_attr(docsrs, doc(cfg(feature = "gis")))]
+pub mod map;
+pub mod message;
+#[cfg(feature = "oauth")]
+#[cfg_attr(docsrs, doc(cfg(feature = "oauth")))]
+pub mod oauth;
+pub mod panic;
+pub mod partial;
+pub mod redact;
+#[cfg(feature = "csvs")]
+#[cfg_attr(docsrs, doc(cfg(feature = "csvs")))]
+pub mod report;
+#[cfg(feature = "gis")]
+#[cfg_attr(docsrs, doc(cfg(feature = "gis")))]
+pub mod shape;
+#[cfg(feature = "time")]
+#[cfg_attr(docsrs, doc(cfg(feature = "time")))]
+pub mod time;
+#[cfg(feature = "trace")]
+#[cfg_attr(docsrs, doc(cfg(feature = "trace")))]
+pub mod trace;
+#[cfg(feature = "icon")]
+#[cfg_attr(docsrs, doc(cfg(feature = "icon")))]
+pub mod ui;
+pub mod validate;
 
 /// The `prelude` module exports library types intended for public use.
 pub mod prelude {
-    pub use crate::error::{Bandage, Clean};
+    pub use crate::auth::{AuthError, AuthReason};
+    #[cfg(feature = "stream")]
+    pub use crate::collect::CleanStream;
+    pub use crate::collect::{AggregateError, CleanIter};
+    #[cfg(all(feature = "env", feature = "serial"))]
+    pub use crate::config::{
+        ConfigError, ConfigIssue, ConfigIssueKind, ConfigLoader, ConfigSource,
+    };
+    #[cfg(feature = "serial")]
+    pub use crate::crash::{CrashReport, CrashReporter};
+    #[cfg(feature = "env")]
+    pub use crate::env::{EnvError, EnvErrorKind};
+    pub use crate::error::{Bandage, BandageKind, Clean};
+    #[cfg(feature = "gis")]
+    pub use crate::features::{FeatureBatch, FeatureError, FeatureParser};
+    #[cfg(feature = "gis")]
+    pub use crate::gis::{BoundingBox, GeometryError, GeometryIssue, GeometryValidator, Winding};
+    #[cfg(feature = "gpu")]
+    pub use crate::gpu::{NoAdapterError, UncapturedError, UncapturedKind};
+    #[cfg(any(feature = "icon", feature = "win"))]
+    pub use crate::icon::{IconError, IconLoader, IconSource, IconStage};
+    #[cfg(feature = "gis")]
+    pub use crate::layer::ErrorLayer;
+    #[cfg(feature = "gis")]
+    pub use crate::map::{MapContext, MapError, MapTarget, Persistence};
+    pub use crate::message::{Locale, MessageId};
+    #[cfg(feature = "oauth")]
+    pub use crate::oauth::{Oauth2Error, Oauth2ErrorKind};
+    pub use crate::panic::{PanicError, PanicHook};
+    pub use crate::partial::{Partial, ThresholdError};
+    #[cfg(feature = "redact")]
+    pub use crate::redact::RedactRule;
+    #[cfg(feature = "csvs")]
+    pub use crate::report::{ErrorReport, Severity};
+    #[cfg(feature = "gis")]
+    pub use crate::shape::{ShapeBatch, ShapeRecord, ShapefileError, ShapefileReader};
+    #[cfg(feature = "time")]
+    pub use crate::time::{DateAttempt, DateFormat, DateParseError, DateParser};
+    #[cfg(feature = "trace")]
+    pub use crate::trace::{TraceErr, TraceFormat, TraceOptions};
+    #[cfg(feature = "icon")]
+    pub use crate::ui::{BoundaryError, ErrorNotice, ErrorQueue};
+    pub use crate::validate::{Constraint, ValidationError, Violation};
 }
@@ -0,0 +1,189 @@
+//! The `map` module wraps errors from the `galileo` map engine with the tile or layer that
+//! failed, and classifies them as transient or permanent, so a map viewer can retry a tile
+//! fetch but report a bad style or projection.
+use std::fmt;
+
+/// The `MapTarget` enum is the part of the map that failed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MapTarget {
+    /// A tile, by zoom level and column and row.
+    Tile {
+        /// The zoom level of the tile.
+        z: u32,
+        /// The column of the tile.
+        x: i32,
+        /// The row of the tile.
+        y: i32,
+    },
+    /// A layer, by name.
+    Layer(String),
+}
+
+impl fmt::Display for MapTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tile { z, x, y } => write!(f, "tile {z}/{x}/{y}"),
+            Self::Layer(name) => write!(f, "layer '{name}'"),
+        }
+    }
+}
+
+/// The `Persistence` enum says whether a map failure may go away if the operation is retried.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Persistence {
+    /// The failure may succeed on retry, such as a network tile fetch.
+    Transient,
+    /// The failure will recur, such as bad data, a bad style or an unsupported projection.
+    Permanent,
+}
+
+/// The `MapError` struct holds an error from the map engine with the tile or layer that failed.
+#[derive(Debug, thiserror::Error)]
+pub struct MapError {
+    target: Option<MapTarget>,
+    persistence: Persistence,
+    #[source]
+    source: Box<dyn std::error::Error + Send + Sync>,
+}
+
+impl MapError {
+    /// The `decoding` method wraps an error from decoding tile or feature data, such as an
+    /// `image::ImageError` or a `geojson::Error`, as a permanent failure.
+    pub fn decoding<E: std::error::Error + Send + Sync + 'static>(error: E) -> Self {
+        Self {
+            target: None,
+            persistence: Persistence::Permanent,
+            source: Box::new(error),
+        }
+    }
+
+    /// The `tile` method records the tile that failed.
+    pub fn tile(mut self, z: u32, x: i32, y: i32) -> Self {
+        self.target = Some(MapTarget::Tile { z, x, y });
+        self
+    }
+
+    /// The `layer` method records the layer that failed.
+    pub fn layer<S: Into<String>>(mut self, name: S) -> Self {
+        self.target = Some(MapTarget::Layer(name.into()));
+        self
+    }
+
+    /// The `target` method returns the tile or layer that failed, if recorded.
+    pub fn target(&self) -> Option<&MapTarget> {
+        self.target.as_ref()
+    }
+
+    /// The `persistence` method returns whether the failure may go away on retry.
+    pub fn persistence(&self) -> Persistence {
+        self.persistence
+    }
+
+    /// The `is_transient` method returns `true` if the failure may go away on retry.
+    pub fn is_transient(&self) -> bool {
+        self.persistence == Persistence::Transient
+    }
+
+    /// The `galileo` method returns the enclosed [`galileo::error::GalileoError`], if the error
+    /// came from the map engine.
+    pub fn galileo(&self) -> Option<&galileo::error::GalileoError> {
+        self.source.downcast_ref()
+    }
+}
+
+impl fmt::Display for MapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(target) = &self.target {
+            write!(f, "{target}: ")?;
+        }
+        write!(f, "{}", self.source)
+    }
+}
+
+impl From<galileo::error::GalileoError> for MapError {
+    fn from(error: galileo::error::GalileoError) -> Self {
+        // Galileo reports failed tile fetches as `IO`; decoding, style and projection problems
+        // have their own variants and will fail the same way again.
+        let persistence = match error {
+            galileo::error::GalileoError::IO => Persistence::Transient,
+            _ => Persistence::Permanent,
+        };
+        Self {
+            target: None,
+            persistence,
+            source: Box::new(error),
+        }
+    }
+}
+
+/// The `MapContext` trait adds the tile or layer that failed to the error of a map operation.
+pub trait MapContext<T> {
+    /// The `tile` method records the tile that failed.
+    fn tile(self, z: u32, x: i32, y: i32) -> Result<T, MapError>;
+
+    /// The `layer` method records the layer that failed.
+    fn layer<S: Into<String>>(self, name: S) -> Result<T, MapError>;
+}
+
+impl<T, E: Into<MapError>> MapContext<T> for Result<T, E> {
+    fn tile(self, z: u32, x: i32, y: i32) -> Result<T, MapError> {
+        self.map_err(|e| e.into().tile(z, x, y))
+    }
+
+    fn layer<S: Into<String>>(self, name: S) -> Result<T, MapError> {
+        self.map_err(|e| e.into().layer(name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::Bandage;
+    use galileo::error::GalileoError;
+
+    #[test]
+    fn only_io_errors_are_transient() {
+        let persistence = |error| MapError::from(error).persistence();
+        assert_eq!(persistence(GalileoError::IO), Persistence::Transient);
+        assert_eq!(persistence(GalileoError::Decoding), Persistence::Permanent);
+        assert_eq!(
+            persistence(GalileoError::Configuration("bad style".to_string())),
+            Persistence::Permanent
+        );
+    }
+
+    #[test]
+    fn decoding_errors_are_permanent() {
+        let error = MapError::decoding(std::fmt::Error);
+        assert!(!error.is_transient());
+        assert!(error.galileo().is_none());
+        assert!(MapError::from(GalileoError::IO).galileo().is_some());
+    }
+
+    #[test]
+    fn context_names_the_tile_or_layer() {
+        let result: Result<(), GalileoError> = Err(GalileoError::IO);
+        let error = result.tile(12, 654, -1).unwrap_err();
+        assert_eq!(
+            error.target(),
+            Some(&MapTarget::Tile {
+                z: 12,
+                x: 654,
+                y: -1
+            })
+        );
+        assert!(error.to_string().starts_with("tile 12/654/-1: "), "{error}");
+        let result: Result<(), GalileoError> = Err(GalileoError::Decoding);
+        let error = result.layer("parcels").unwrap_err();
+        assert!(
+            error.to_string().starts_with("layer 'parcels': "),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn bandage_keeps_the_persistence() {
+        assert!(Bandage::from(GalileoError::IO).is_retryable());
+        assert!(!Bandage::from(GalileoError::Decoding).is_retryable());
+    }
+}
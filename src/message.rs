@@ -0,0 +1,751 @@
+//! The `message` module provides short, non-technical messages for showing a [`Bandage`] to the
+//! public, kept apart from the developer-facing `Display` and `Debug` output.
+//!
+//! Messages are available in each [`Locale`], with arguments such as field and file names filled
+//! in.  Applications can replace the message for any [`Bandage::code`] with
+//! [`set_user_message`] or [`set_localized_user_message`].
+//!
+//! Every variant maps to a [`MessageId`], and each locale's catalog matches on every
+//! `MessageId` without a wildcard, so a message missing from a catalog fails to compile.  The
+//! tests check that each translation is filled in and takes the same arguments as the English.
+use crate::prelude::Bandage;
+use crate::redact::redact;
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+
+/// Overrides keyed by locale, or `None` for every locale, and code.
+static OVERRIDES: RwLock<BTreeMap<(Option<Locale>, String), String>> = RwLock::new(BTreeMap::new());
+
+/// The `Locale` enum lists the languages with user-facing message catalogs.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Locale {
+    /// English.
+    #[default]
+    English,
+    /// Spanish.
+    Spanish,
+}
+
+impl Locale {
+    /// The `ALL` constant lists every supported locale.
+    pub const ALL: [Locale; 2] = [Locale::English, Locale::Spanish];
+
+    /// The `tag` method returns the BCP 47 language tag of the locale, such as `"es"`.
+    pub fn tag(&self) -> &'static str {
+        match self {
+            Self::English => "en",
+            Self::Spanish => "es",
+        }
+    }
+
+    /// The `from_tag` method returns the locale for a BCP 47 language tag such as `"es-MX"`,
+    /// matching on the primary language, or `None` if the language is not supported.
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let primary = tag.trim().split(['-', '_']).next()?.to_lowercase();
+        Self::ALL.into_iter().find(|l| l.tag() == primary)
+    }
+
+    /// The `from_accept_language` method picks the supported locale with the highest quality
+    /// value from an `Accept-Language` header, defaulting to English.  A quality of `0` marks a
+    /// language as not acceptable, so it is never picked, and a quality that is not a number
+    /// from `0` to `1` counts as `0`.
+    pub fn from_accept_language(header: &str) -> Self {
+        let mut best: Option<(f32, Self)> = None;
+        for item in header.split(',') {
+            let mut parts = item.split(';');
+            let Some(locale) = parts.next().and_then(Self::from_tag) else {
+                continue;
+            };
+            let quality = parts
+                .find_map(|p| p.trim().strip_prefix("q="))
+                .map_or(1.0, |q| {
+                    q.trim()
+                        .parse::<f32>()
+                        .ok()
+                        .filter(|q| (0.0..=1.0).contains(q))
+                        .unwrap_or(0.0)
+                });
+            if quality <= 0.0 {
+                continue;
+            }
+            if best.is_none_or(|(q, _)| quality > q) {
+                best = Some((quality, locale));
+            }
+        }
+        best.map(|(_, locale)| locale).unwrap_or_default()
+    }
+}
+
+/// The `MessageId` enum identifies a user-facing message.  Several variants of [`Bandage`] share
+/// each message.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MessageId {
+    /// The request did not carry credentials.
+    AuthUnauthenticated,
+    /// The session expired or the grant was revoked.
+    AuthExpired,
+    /// The user lacks permission.
+    AuthForbidden,
+    /// The credentials could not be verified.
+    AuthInvalidToken,
+    /// Multi-factor authentication is required.
+    AuthMfaRequired,
+    /// A chart could not be drawn.
+    Chart,
+    /// The service is misconfigured.
+    Config,
+    /// The database could not be reached.
+    Database,
+    /// A date could not be understood.
+    Date,
+    /// A date could not be understood, with the `input` argument.
+    DateInput,
+    /// A window could not be displayed.
+    Display,
+    /// A file could not be read or saved.
+    File,
+    /// A file name is invalid, with the `name` argument.
+    FileName,
+    /// Input is not in the expected format.
+    Format,
+    /// An unexpected error.
+    Generic,
+    /// An image could not be loaded.
+    Image,
+    /// Some fields are invalid, with the `fields` argument.
+    InvalidFields,
+    /// Map data could not be read.
+    Map,
+    /// Required information is missing, with the `fields` argument.
+    MissingInformation,
+    /// A required service could not be reached.
+    Service,
+    /// Signing in failed.
+    SignInFailed,
+    /// A size is invalid.
+    Size,
+    /// A spreadsheet could not be read or written.
+    Spreadsheet,
+    /// A web address is invalid.
+    WebAddress,
+    /// A whole number was expected.
+    WholeNumber,
+}
+
+impl MessageId {
+    /// The `ALL` constant lists every message.
+    pub const ALL: [MessageId; 25] = [
+        Self::AuthUnauthenticated,
+        Self::AuthExpired,
+        Self::AuthForbidden,
+        Self::AuthInvalidToken,
+        Self::AuthMfaRequired,
+        Self::Chart,
+        Self::Config,
+        Self::Database,
+        Self::Date,
+        Self::DateInput,
+        Self::Display,
+        Self::File,
+        Self::FileName,
+        Self::Format,
+        Self::Generic,
+        Self::Image,
+        Self::InvalidFields,
+        Self::Map,
+        Self::MissingInformation,
+        Self::Service,
+        Self::SignInFailed,
+        Self::Size,
+        Self::Spreadsheet,
+        Self::WebAddress,
+        Self::WholeNumber,
+    ];
+}
+
+/// The `set_user_message` function replaces the user-facing message in every locale for errors
+/// with the given [`Bandage::code`].  Arguments such as `{fields}` are filled in as for the
+/// built-in messages.
+pub fn set_user_message<S: Into<String>>(code: &str, message: S) {
+    if let Ok(mut overrides) = OVERRIDES.write() {
+        overrides.insert((None, code.to_string()), message.into());
+    }
+}
+
+/// The `set_localized_user_message` function replaces the user-facing message in `locale` for
+/// errors with the given [`Bandage::code`], taking precedence over [`set_user_message`].
+pub fn set_localized_user_message<S: Into<String>>(locale: Locale, code: &str, message: S) {
+    if let Ok(mut overrides) = OVERRIDES.write() {
+        overrides.insert((Some(locale), code.to_string()), message.into());
+    }
+}
+
+/// The `clear_user_message` function restores the default user-facing message in every locale
+/// for errors with the given [`Bandage::code`].
+pub fn clear_user_message(code: &str) {
+    if let Ok(mut overrides) = OVERRIDES.write() {
+        overrides.retain(|(_, c), _| c != code);
+    }
+}
+
+impl Bandage {
+    /// The `user_message` method returns a short, non-technical message in English that is safe
+    /// to show to members of the public.
+    pub fn user_message(&self) -> String {
+        self.user_message_in(Locale::English)
+    }
+
+    /// The `user_message_in` method returns the user-facing message in `locale`, using an
+    /// override if one is set.
+    pub fn user_message_in(&self, locale: Locale) -> String {
+        let code = self.code().to_string();
+        let custom = OVERRIDES.read().ok().and_then(|overrides| {
+            overrides
+                .get(&(Some(locale), code.clone()))
+                .or_else(|| overrides.get(&(None, code)))
+                .cloned()
+        });
+        let template = custom.unwrap_or_else(|| self.message_id().template(locale).to_string());
+        fill(&template, &self.message_args())
+    }
+
+    /// The `message_args` method returns the named arguments filled into the user-facing
+    /// message, such as the missing `fields` of a [`Bandage::UserBuild`], with secrets and email
+    /// addresses redacted.
+    pub fn message_args(&self) -> Vec<(&'static str, String)> {
+        match self {
+            Self::FileName(name) => vec![("name", redact(&name.to_string_lossy()))],
+            Self::UserBuild { value } => vec![("fields", redact(&value.join(", ")))],
+            Self::Validation(e) => {
+                let mut fields: Vec<&str> = Vec::new();
+                for violation in e.violations() {
+                    if !fields.contains(&violation.path()) {
+                        fields.push(violation.path());
+                    }
+                }
+                vec![("fields", redact(&fields.join(", ")))]
+            }
+            #[cfg(feature = "time")]
+            Self::DateParse(e) => vec![("input", redact(e.input()))],
+            // Matches `message_id`, which takes the message of the first error.
+            Self::Aggregate(e) => e
+                .errors()
+                .first()
+                .map_or_else(Vec::new, Bandage::message_args),
+            _ => Vec::new(),
+        }
+    }
+
+    /// The `message_id` method returns the [`MessageId`] of the built-in user-facing message for
+    /// the error.
+    pub fn message_id(&self) -> MessageId {
+        match self {
+            Self::Auth(e) => match e.reason() {
+                crate::auth::AuthReason::Unauthenticated => MessageId::AuthUnauthenticated,
+                crate::auth::AuthReason::Expired => MessageId::AuthExpired,
+                crate::auth::AuthReason::Forbidden => MessageId::AuthForbidden,
+                crate::auth::AuthReason::InvalidToken => MessageId::AuthInvalidToken,
+                crate::auth::AuthReason::MfaRequired => MessageId::AuthMfaRequired,
+            },
+            Self::Env(_) => MessageId::Config,
+            Self::FileName(_) => MessageId::FileName,
+            Self::Int(_) => MessageId::WholeNumber,
+            Self::Io(_) => MessageId::File,
+            Self::Panic(_) => MessageId::Generic,
+            Self::Parse => MessageId::Format,
+            Self::UserBuild { .. } => MessageId::MissingInformation,
+            Self::Validation(_) => MessageId::InvalidFields,
+            Self::Utf8(_) => MessageId::Format,
+            Self::Hint(_) => MessageId::Generic,
+            Self::Unknown => MessageId::Generic,
+            #[cfg(feature = "redact")]
+            Self::Regex(_) => MessageId::Generic,
+            Self::BatchThreshold(_) => MessageId::Generic,
+            Self::Aggregate(e) => e
+                .errors()
+                .first()
+                .map_or(MessageId::Generic, Bandage::message_id),
+            #[cfg(feature = "icon")]
+            Self::BadIcon(_) => MessageId::Image,
+            #[cfg(any(feature = "icon", feature = "win"))]
+            Self::Icon(_) => MessageId::Image,
+            #[cfg(feature = "bin")]
+            Self::Bin(_) => MessageId::Format,
+            #[cfg(feature = "byte")]
+            Self::Byte(_) => MessageId::Size,
+            #[cfg(feature = "csvs")]
+            Self::Csv(_) => MessageId::Spreadsheet,
+            #[cfg(feature = "req")]
+            Self::Http(_) => MessageId::Service,
+            #[cfg(feature = "img")]
+            Self::Image(_) => MessageId::Image,
+            #[cfg(feature = "oauth")]
+            Self::Oauth2(e) if e.requires_reauthentication() => MessageId::AuthExpired,
+            #[cfg(feature = "oauth")]
+            Self::Oauth2(_) => MessageId::SignInFailed,
+            #[cfg(feature = "serial")]
+            Self::Serialize(_) => MessageId::Format,
+            #[cfg(feature = "serial")]
+            Self::SerdeJson(_) => MessageId::Format,
+            #[cfg(feature = "sql")]
+            Self::Sqlx(_) => MessageId::Database,
+            #[cfg(feature = "sql")]
+            Self::Migrate(_) => MessageId::Database,
+            #[cfg(feature = "id")]
+            Self::Uuid(_) => MessageId::Format,
+            #[cfg(feature = "urls")]
+            Self::Url(_) => MessageId::WebAddress,
+            #[cfg(feature = "plot")]
+            Self::BitMap(_) => MessageId::Chart,
+            #[cfg(feature = "plot")]
+            Self::Plot(_) => MessageId::Chart,
+            #[cfg(feature = "gis")]
+            Self::GeoJson(_) => MessageId::Map,
+            #[cfg(feature = "gis")]
+            Self::GeoJsonFeature(_) => MessageId::Map,
+            #[cfg(feature = "gis")]
+            Self::Geometry(_) => MessageId::Map,
+            #[cfg(feature = "gis")]
+            Self::Galileo(_) => MessageId::Map,
+            #[cfg(feature = "trace")]
+            Self::TraceInit(_) => MessageId::Config,
+            #[cfg(feature = "trace")]
+            Self::TraceFilter(_) => MessageId::Config,
+            #[cfg(feature = "route")]
+            Self::Axum(_) => MessageId::Service,
+            #[cfg(feature = "route")]
+            Self::AxumHttp(_) => MessageId::Service,
+            #[cfg(feature = "hype")]
+            Self::Hyper(_) => MessageId::Service,
+            #[cfg(feature = "hype")]
+            Self::HyperUtil(_) => MessageId::Service,
+            #[cfg(feature = "gis")]
+            Self::Shapefile(_) => MessageId::Map,
+            #[cfg(feature = "gis")]
+            Self::ShapefileRecord(_) => MessageId::Map,
+            #[cfg(feature = "win")]
+            Self::EventLoop(_) => MessageId::Display,
+            #[cfg(feature = "win")]
+            Self::WinOs(_) => MessageId::Display,
+            #[cfg(feature = "win")]
+            Self::WinitIcon(_) => MessageId::Image,
+            #[cfg(feature = "win")]
+            Self::WinitExternal(_) => MessageId::Display,
+            #[cfg(feature = "win")]
+            Self::WinitNotSupported(_) => MessageId::Display,
+            #[cfg(feature = "win")]
+            Self::EventLoopClosed => MessageId::Display,
+            #[cfg(feature = "gpu")]
+            Self::WgpuSurface(_) => MessageId::Display,
+            #[cfg(feature = "gpu")]
+            Self::WgpuDevice(_) => MessageId::Display,
+            #[cfg(feature = "gpu")]
+            Self::WgpuFrame(_) => MessageId::Display,
+            #[cfg(feature = "gpu")]
+            Self::WgpuBuffer(_) => MessageId::Display,
+            #[cfg(feature = "gpu")]
+            Self::WgpuAdapter(_) => MessageId::Display,
+            #[cfg(feature = "gpu")]
+            Self::WgpuUncaptured(_) => MessageId::Display,
+            #[cfg(feature = "parse")]
+            Self::Nom(_) => MessageId::Format,
+            #[cfg(feature = "sql")]
+            Self::Tiberius(_) => MessageId::Database,
+            #[cfg(feature = "env")]
+            Self::Dotenv(_) => MessageId::Config,
+            #[cfg(all(feature = "env", feature = "serial"))]
+            Self::Config(_) => MessageId::Config,
+            #[cfg(feature = "env")]
+            Self::EnvVar(_) => MessageId::Config,
+            #[cfg(feature = "time")]
+            Self::DateParse(_) => MessageId::DateInput,
+            #[cfg(feature = "time")]
+            Self::Jiff(_) => MessageId::Date,
+        }
+    }
+}
+
+impl MessageId {
+    /// The `template` method returns the text of the message in `locale`, with arguments in
+    /// braces such as `{fields}`.
+    pub fn template(&self, locale: Locale) -> &'static str {
+        match locale {
+            Locale::English => self.english(),
+            Locale::Spanish => self.spanish(),
+        }
+    }
+
+    fn english(&self) -> &'static str {
+        match self {
+            Self::AuthUnauthenticated => "Please sign in to continue.",
+            Self::AuthExpired => "Your session has expired. Please sign in again.",
+            Self::AuthForbidden => "You don't have permission to do that.",
+            Self::AuthInvalidToken => "We couldn't verify your sign-in. Please sign in again.",
+            Self::AuthMfaRequired => "Please complete multi-factor authentication to continue.",
+            Self::Chart => "We couldn't draw the chart.",
+            Self::Config => "This service is not set up correctly. Please contact support.",
+            Self::Database => "We couldn't reach the database. Please try again in a few minutes.",
+            Self::Date => "We couldn't understand that date.",
+            Self::DateInput => "We couldn't understand the date \"{input}\".",
+            Self::Display => "The window could not be displayed. Please restart the application.",
+            Self::File => "We couldn't read or save a file. Please try again.",
+            Self::FileName => "The file name \"{name}\" isn't valid.",
+            Self::Format => "Some of the information provided is not in the expected format.",
+            Self::Generic => "Something went wrong. Please try again.",
+            Self::Image => "We couldn't load an image.",
+            Self::InvalidFields => "Please check the following information: {fields}.",
+            Self::Map => "We couldn't read the map data.",
+            Self::MissingInformation => "Some required information is missing: {fields}.",
+            Self::Service => {
+                "We couldn't reach a required service. Please try again in a few minutes."
+            }
+            Self::SignInFailed => "We couldn't sign you in. Please try again.",
+            Self::Size => "Please enter a valid size, such as 10 MB.",
+            Self::Spreadsheet => "We couldn't read or write the spreadsheet.",
+            Self::WebAddress => "That web address isn't valid.",
+            Self::WholeNumber => "Please enter a whole number.",
+        }
+    }
+
+    fn spanish(&self) -> &'static str {
+        match self {
+            Self::AuthUnauthenticated => "Inicie sesión para continuar.",
+            Self::AuthExpired => "Su sesión ha expirado. Vuelva a iniciar sesión.",
+            Self::AuthForbidden => "No tiene permiso para hacer eso.",
+            Self::AuthInvalidToken => {
+                "No pudimos verificar su inicio de sesión. Vuelva a iniciar sesión."
+            }
+            Self::AuthMfaRequired => {
+                "Complete la autenticación de múltiples factores para continuar."
+            }
+            Self::Chart => "No pudimos dibujar el gráfico.",
+            Self::Config => {
+                "Este servicio no está configurado correctamente. Comuníquese con soporte técnico."
+            }
+            Self::Database => {
+                "No pudimos conectarnos a la base de datos. Inténtelo de nuevo en unos minutos."
+            }
+            Self::Date => "No pudimos entender esa fecha.",
+            Self::DateInput => "No pudimos entender la fecha \"{input}\".",
+            Self::Display => "No se pudo mostrar la ventana. Reinicie la aplicación.",
+            Self::File => "No pudimos leer ni guardar un archivo. Inténtelo de nuevo.",
+            Self::FileName => "El nombre de archivo \"{name}\" no es válido.",
+            Self::Format => "Parte de la información proporcionada no tiene el formato esperado.",
+            Self::Generic => "Algo salió mal. Inténtelo de nuevo.",
+            Self::Image => "No pudimos cargar una imagen.",
+            Self::InvalidFields => "Revise la siguiente información: {fields}.",
+            Self::Map => "No pudimos leer los datos del mapa.",
+            Self::MissingInformation => "Falta información obligatoria: {fields}.",
+            Self::Service => {
+                "No pudimos comunicarnos con un servicio necesario. Inténtelo de nuevo en unos minutos."
+            }
+            Self::SignInFailed => "No pudimos iniciar su sesión. Inténtelo de nuevo.",
+            Self::Size => "Ingrese un tamaño válido, como 10 MB.",
+            Self::Spreadsheet => "No pudimos leer ni escribir la hoja de cálculo.",
+            Self::WebAddress => "Esa dirección web no es válida.",
+            Self::WholeNumber => "Ingrese un número entero.",
+        }
+    }
+}
+
+/// Fills each `{name}` placeholder in `template` with its value in `args`, in one pass so a value
+/// that itself contains a placeholder is left as it is.  Unknown placeholders are kept.
+fn fill(template: &str, args: &[(&str, String)]) -> String {
+    let mut text = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        text.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let arg = after.find('}').and_then(|end| {
+            let name = &after[..end];
+            let (_, value) = args.iter().find(|(arg, _)| *arg == name)?;
+            Some((value, end))
+        });
+        match arg {
+            Some((value, end)) => {
+                text.push_str(value);
+                rest = &after[end + 1..];
+            }
+            None => {
+                text.push('{');
+                rest = after;
+            }
+        }
+    }
+    text.push_str(rest);
+    text
+}
+
+/// The `Localized` struct renders a [`Bandage`] as an HTTP response with its user-facing message
+/// in a given locale.  Create one with [`Bandage::localize`].
+#[cfg(feature = "route")]
+#[cfg_attr(docsrs, doc(cfg(feature = "route")))]
+#[derive(Debug)]
+pub struct Localized {
+    error: Bandage,
+    locale: Locale,
+}
+
+#[cfg(feature = "route")]
+#[cfg_attr(docsrs, doc(cfg(feature = "route")))]
+impl Bandage {
+    /// The `localize` method pairs the error with `locale` for rendering through axum.
+    pub fn localize(self, locale: Locale) -> Localized {
+        Localized {
+            error: self,
+            locale,
+        }
+    }
+}
+
+#[cfg(feature = "route")]
+impl axum::response::IntoResponse for Localized {
+    fn into_response(self) -> axum::response::Response {
+        use axum::http::{header, HeaderValue};
+        let message = self.error.user_message_in(self.locale);
+        let mut response = match self.error {
+            // Keeps the JSON body of field errors, adding the message to it.
+            Bandage::Validation(error) => (
+                axum::http::StatusCode::UNPROCESSABLE_ENTITY,
+                axum::Json(LocalizedViolations {
+                    message,
+                    error: &error,
+                }),
+            )
+                .into_response(),
+            error => {
+                let mut response = error.into_response();
+                *response.body_mut() = axum::body::Body::from(message);
+                response.headers_mut().insert(
+                    header::CONTENT_TYPE,
+                    HeaderValue::from_static("text/plain; charset=utf-8"),
+                );
+                response
+            }
+        };
+        if let Ok(value) = HeaderValue::from_str(self.locale.tag()) {
+            response
+                .headers_mut()
+                .insert(header::CONTENT_LANGUAGE, value);
+        }
+        response
+    }
+}
+
+/// The JSON body of a localized [`Bandage::Validation`] response.
+#[cfg(feature = "route")]
+#[derive(serde::Serialize)]
+struct LocalizedViolations<'a> {
+    message: String,
+    #[serde(flatten)]
+    error: &'a crate::validate::ValidationError,
+}
+
+/// Extracts the [`Locale`] from the `Accept-Language` header, defaulting to English.
+#[cfg(feature = "route")]
+#[cfg_attr(docsrs, doc(cfg(feature = "route")))]
+impl<S: Send + Sync> axum::extract::FromRequestParts<S> for Locale {
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(
+        parts: &mut axum::http::request::Parts,
+        _state: &S,
+    ) -> Result<Self, Self::Rejection> {
+        let locale = parts
+            .headers
+            .get(axum::http::header::ACCEPT_LANGUAGE)
+            .and_then(|value| value.to_str().ok())
+            .map(Self::from_accept_language)
+            .unwrap_or_default();
+        Ok(locale)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validate::{ValidationError, Violation};
+
+    /// Returns the `{argument}` names in `template`.
+    fn arguments(template: &str) -> Vec<&str> {
+        let mut names: Vec<&str> = template
+            .split('{')
+            .skip(1)
+            .filter_map(|rest| rest.split_once('}').map(|(name, _)| name))
+            .collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// Returns an error for each message that takes arguments.
+    fn singles() -> Vec<Bandage> {
+        #[cfg(feature = "time")]
+        let date = crate::time::DateParser::empty()
+            .datetime("not a date")
+            .err();
+        #[cfg(not(feature = "time"))]
+        let date = None;
+        vec![
+            Bandage::Unknown,
+            Bandage::FileName("parcels.shp".into()),
+            Bandage::UserBuild {
+                value: vec!["owner".to_string(), "zip".to_string()],
+            },
+            ValidationError::new()
+                .violation(Violation::required("address.zip"))
+                .into(),
+        ]
+        .into_iter()
+        .chain(date)
+        .collect()
+    }
+
+    /// Returns the errors of [`singles`], and each again as the first of an aggregate.
+    fn samples() -> Vec<Bandage> {
+        let aggregates = singles().into_iter().map(|error| {
+            let errors = vec![error, Bandage::Hint("second".to_string())];
+            crate::collect::AggregateError::new(errors).into()
+        });
+        singles().into_iter().chain(aggregates).collect()
+    }
+
+    #[test]
+    fn every_message_is_translated() {
+        assert_eq!(
+            MessageId::ALL.len(),
+            MessageId::ALL
+                .iter()
+                .collect::<std::collections::BTreeSet<_>>()
+                .len()
+        );
+        for id in MessageId::ALL {
+            let english = arguments(id.template(Locale::English));
+            for locale in Locale::ALL {
+                let template = id.template(locale);
+                assert!(!template.trim().is_empty(), "{id:?} is empty in {locale:?}");
+                assert_eq!(
+                    arguments(template),
+                    english,
+                    "{id:?} takes different arguments in {locale:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn every_argument_is_filled() {
+        for error in samples() {
+            for locale in Locale::ALL {
+                let message = error.user_message_in(locale);
+                assert!(
+                    !message.contains('{') && !message.contains('}'),
+                    "{} left an argument unfilled in {locale:?}: {message}",
+                    error.code()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn arguments_are_filled_once() {
+        let args = [
+            ("name", "{fields}".to_string()),
+            ("fields", "zip".to_string()),
+        ];
+        assert_eq!(
+            fill("{name} needs {fields} {unknown} {", &args),
+            "{fields} needs zip {unknown} {"
+        );
+    }
+
+    #[test]
+    fn accept_language_picks_highest_quality() {
+        assert_eq!(
+            Locale::from_accept_language("es-MX,es;q=0.9,en;q=0.8"),
+            Locale::Spanish
+        );
+        assert_eq!(
+            Locale::from_accept_language("fr, en;q=0.5, es;q=0.7"),
+            Locale::Spanish
+        );
+        assert_eq!(
+            Locale::from_accept_language("en-US, es;q=0.9"),
+            Locale::English
+        );
+        assert_eq!(Locale::from_accept_language("fr, de"), Locale::English);
+        assert_eq!(Locale::from_accept_language(""), Locale::English);
+    }
+
+    #[test]
+    fn accept_language_ignores_invalid_quality() {
+        assert_eq!(
+            Locale::from_accept_language("es;q=abc, en;q=0.9"),
+            Locale::English
+        );
+        assert_eq!(
+            Locale::from_accept_language("es;q=2, en;q=0.1"),
+            Locale::English
+        );
+        assert_eq!(Locale::from_accept_language("es;q=NaN"), Locale::English);
+    }
+
+    #[test]
+    fn accept_language_skips_unacceptable() {
+        assert_eq!(Locale::from_accept_language("es;q=0"), Locale::English);
+        assert_eq!(
+            Locale::from_accept_language("es;q=0, en;q=0.1"),
+            Locale::English
+        );
+        assert_eq!(
+            Locale::from_accept_language("en;q=0.0, es;q=0.2"),
+            Locale::Spanish
+        );
+    }
+
+    #[test]
+    fn tag_matches_primary_language() {
+        assert_eq!(Locale::from_tag("es-MX"), Some(Locale::Spanish));
+        assert_eq!(Locale::from_tag("EN_us"), Some(Locale::English));
+        assert_eq!(Locale::from_tag("fr"), None);
+    }
+
+    #[cfg(all(feature = "route", feature = "stream"))]
+    fn body(response: axum::response::Response) -> String {
+        let bytes =
+            futures::executor::block_on(axum::body::to_bytes(response.into_body(), usize::MAX))
+                .unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[cfg(all(feature = "route", feature = "stream"))]
+    #[test]
+    fn localized_validation_keeps_json() {
+        use axum::response::IntoResponse;
+        let error: Bandage = ValidationError::new()
+            .violation(Violation::required("address.zip"))
+            .into();
+        let response = error.localize(Locale::Spanish).into_response();
+        assert_eq!(response.status(), 422);
+        let headers = response.headers();
+        assert_eq!(headers["content-type"], "application/json");
+        assert_eq!(headers["content-language"], "es");
+        let body = body(response);
+        assert_eq!(
+            body,
+            r#"{"message":"Revise la siguiente información: address.zip.","violations":[{"path":"address.zip","code":"required"}]}"#
+        );
+    }
+
+    #[cfg(all(feature = "route", feature = "stream"))]
+    #[test]
+    fn localized_text_is_plain() {
+        use axum::response::IntoResponse;
+        let response = Bandage::Unknown.localize(Locale::Spanish).into_response();
+        assert_eq!(response.status(), 500);
+        assert_eq!(
+            response.headers()["content-type"],
+            "text/plain; charset=utf-8"
+        );
+        assert_eq!(body(response), "Algo salió mal. Inténtelo de nuevo.");
+    }
+}
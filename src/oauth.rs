@@ -0,0 +1,181 @@
+//! The `oauth` module holds [`Oauth2Error`], a structured wrapper over errors from the `oauth2`
+//! crate that is not tied to a particular HTTP client or error response type.
+use oauth2::{ErrorResponse, RequestTokenError};
+
+/// The `Oauth2ErrorKind` enum mirrors the variants of [`oauth2::RequestTokenError`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Oauth2ErrorKind {
+    /// The authorization server returned an error response.
+    ServerResponse,
+    /// The request failed to send or the response failed to arrive.
+    Request,
+    /// The server response could not be parsed.
+    Parse,
+    /// Some other error occurred, such as an unexpected server response.
+    Other,
+}
+
+/// The `Oauth2Error` struct holds the details of a failed request to an authorization server.
+///
+/// Any [`oauth2::RequestTokenError`] converts into an `Oauth2Error`, regardless of the HTTP client
+/// error or the error response type, so token exchange, device code, introspection and revocation
+/// requests can all use `?`.
+#[derive(Debug, thiserror::Error)]
+#[error("{message}")]
+pub struct Oauth2Error {
+    kind: Oauth2ErrorKind,
+    message: String,
+    error: Option<String>,
+    error_description: Option<String>,
+    error_uri: Option<String>,
+    #[source]
+    source: Option<Box<dyn std::error::Error + Send + Sync>>,
+}
+
+impl Oauth2Error {
+    /// The `kind` method returns the [`Oauth2ErrorKind`] of the error.
+    pub fn kind(&self) -> Oauth2ErrorKind {
+        self.kind
+    }
+
+    /// The `error` method returns the `error` code from the server response, if any.
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// The `error_description` method returns the `error_description` field from the server
+    /// response, if any.
+    pub fn error_description(&self) -> Option<&str> {
+        self.error_description.as_deref()
+    }
+
+    /// The `error_uri` method returns the `error_uri` field from the server response, if any.
+    pub fn error_uri(&self) -> Option<&str> {
+        self.error_uri.as_deref()
+    }
+
+    /// The `requires_reauthentication` method returns `true` when the server rejected the grant
+    /// or the client credentials (`invalid_grant` or `invalid_client`), meaning the user or
+    /// client must authenticate again rather than retry.
+    pub fn requires_reauthentication(&self) -> bool {
+        matches!(self.error(), Some("invalid_grant" | "invalid_client"))
+    }
+}
+
+impl<RE, T> From<RequestTokenError<RE, T>> for Oauth2Error
+where
+    RE: std::error::Error + Send + Sync + 'static,
+    T: ErrorResponse + Send + Sync + 'static,
+{
+    fn from(input: RequestTokenError<RE, T>) -> Self {
+        let message = input.to_string();
+        match input {
+            RequestTokenError::ServerResponse(response) => {
+                // `ErrorResponse` only promises `Serialize`, so read the RFC 6749 fields back out
+                // of the serialized form.
+                let value = serde_json::to_value(&response).unwrap_or_default();
+                let field = |name: &str| value.get(name).and_then(|v| v.as_str()).map(String::from);
+                Self {
+                    kind: Oauth2ErrorKind::ServerResponse,
+                    message,
+                    error: field("error"),
+                    error_description: field("error_description"),
+                    error_uri: field("error_uri"),
+                    source: None,
+                }
+            }
+            RequestTokenError::Request(error) => Self {
+                kind: Oauth2ErrorKind::Request,
+                message,
+                error: None,
+                error_description: None,
+                error_uri: None,
+                source: Some(Box::new(error)),
+            },
+            RequestTokenError::Parse(error, _) => Self {
+                kind: Oauth2ErrorKind::Parse,
+                message,
+                error: None,
+                error_description: None,
+                error_uri: None,
+                source: Some(Box::new(error)),
+            },
+            RequestTokenError::Other(_) => Self {
+                kind: Oauth2ErrorKind::Other,
+                message,
+                error: None,
+                error_description: None,
+                error_uri: None,
+                source: None,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::{Bandage, MessageId};
+    use oauth2::basic::BasicErrorResponse;
+
+    type TokenError = RequestTokenError<std::io::Error, BasicErrorResponse>;
+
+    fn server(json: &str) -> Oauth2Error {
+        let response: BasicErrorResponse = serde_json::from_str(json).unwrap();
+        TokenError::ServerResponse(response).into()
+    }
+
+    #[test]
+    fn server_response_fields() {
+        let error = server(
+            r#"{
+                "error": "invalid_scope",
+                "error_description": "scope parcels.write is not allowed",
+                "error_uri": "https://login.example.com/errors/scope"
+            }"#,
+        );
+        assert_eq!(error.kind(), Oauth2ErrorKind::ServerResponse);
+        assert_eq!(error.error(), Some("invalid_scope"));
+        assert_eq!(
+            error.error_description(),
+            Some("scope parcels.write is not allowed")
+        );
+        assert_eq!(
+            error.error_uri(),
+            Some("https://login.example.com/errors/scope")
+        );
+        assert!(!error.requires_reauthentication());
+        assert!(error.to_string().contains("invalid_scope"));
+    }
+
+    #[test]
+    fn rejected_grant_requires_reauthentication() {
+        for code in ["invalid_grant", "invalid_client"] {
+            let error = server(&format!(r#"{{"error": "{code}"}}"#));
+            assert!(error.requires_reauthentication(), "{code}");
+            assert_eq!(error.error_description(), None);
+            let error = Bandage::from(error);
+            assert_eq!(error.message_id(), MessageId::AuthExpired);
+            assert!(!error.is_retryable());
+        }
+    }
+
+    #[test]
+    fn request_failures_are_retryable() {
+        let error: Oauth2Error = TokenError::Request(std::io::Error::other("timed out")).into();
+        assert_eq!(error.kind(), Oauth2ErrorKind::Request);
+        assert_eq!(error.error(), None);
+        assert!(std::error::Error::source(&error).is_some());
+        let error = Bandage::from(error);
+        assert!(error.is_retryable());
+        assert_eq!(error.message_id(), MessageId::SignInFailed);
+    }
+
+    #[test]
+    fn other_failures_keep_the_message() {
+        let error: Oauth2Error = TokenError::Other("unexpected status 302".to_string()).into();
+        assert_eq!(error.kind(), Oauth2ErrorKind::Other);
+        assert!(error.to_string().contains("unexpected status 302"));
+        assert!(std::error::Error::source(&error).is_none());
+    }
+}
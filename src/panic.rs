@@ -0,0 +1,266 @@
+//! The `panic` module installs a panic hook that records panics as [`PanicError`] values, and
+//! provides [`catch`] helpers that turn a panic in a closure into a [`Bandage`].
+use crate::prelude::{Bandage, Clean};
+use std::cell::{Cell, RefCell};
+
+thread_local! {
+    /// The last panic recorded by the hook on this thread, for [`catch`] to pick up.
+    static LAST_PANIC: RefCell<Option<PanicError>> = const { RefCell::new(None) };
+    /// The number of [`catch`] calls running on this thread, so the hook can tell a panic that
+    /// will be returned as an error from one that ends the thread.
+    static CATCHING: Cell<usize> = const { Cell::new(0) };
+}
+
+/// The `PanicError` struct holds the details of a panic.
+#[derive(Debug, Clone, thiserror::Error)]
+pub struct PanicError {
+    message: String,
+    location: Option<String>,
+    thread: Option<String>,
+    backtrace: Option<String>,
+    span: Option<String>,
+}
+
+impl PanicError {
+    /// The `message` method returns the panic message.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// The `location` method returns the `file:line:column` where the panic occurred.
+    pub fn location(&self) -> Option<&str> {
+        self.location.as_deref()
+    }
+
+    /// The `thread` method returns the name of the panicking thread.
+    pub fn thread(&self) -> Option<&str> {
+        self.thread.as_deref()
+    }
+
+    /// The `backtrace` method returns the backtrace captured by the panic hook.
+    pub fn backtrace(&self) -> Option<&str> {
+        self.backtrace.as_deref()
+    }
+
+    /// The `span` method returns the `tracing` span trace active when the panic occurred, if the
+    /// `trace` feature is enabled and an error layer is installed.
+    pub fn span(&self) -> Option<&str> {
+        self.span.as_deref()
+    }
+
+    fn from_payload(payload: &(dyn std::any::Any + Send)) -> Self {
+        let message = if let Some(message) = payload.downcast_ref::<&str>() {
+            message.to_string()
+        } else if let Some(message) = payload.downcast_ref::<String>() {
+            message.clone()
+        } else {
+            "panic with a non-string payload".to_string()
+        };
+        Self {
+            message,
+            location: None,
+            thread: std::thread::current().name().map(String::from),
+            backtrace: None,
+            span: None,
+        }
+    }
+
+    fn from_hook(info: &std::panic::PanicHookInfo<'_>) -> Self {
+        let mut panic = Self::from_payload(info.payload());
+        panic.location = info.location().map(|l| l.to_string());
+        panic.backtrace = Some(std::backtrace::Backtrace::force_capture().to_string());
+        #[cfg(feature = "trace")]
+        {
+            let span = tracing_error::SpanTrace::capture();
+            if span.status() == tracing_error::SpanTraceStatus::CAPTURED {
+                panic.span = Some(span.to_string());
+            }
+        }
+        panic
+    }
+}
+
+impl std::fmt::Display for PanicError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)?;
+        if let Some(location) = &self.location {
+            write!(f, " at {location}")?;
+        }
+        if let Some(thread) = &self.thread {
+            write!(f, " on thread '{thread}'")?;
+        }
+        Ok(())
+    }
+}
+
+/// The `PanicHook` struct configures the hook installed by [`PanicHook::install`].
+#[derive(Debug, Clone, Default)]
+pub struct PanicHook {
+    #[cfg(feature = "serial")]
+    reporter: Option<crate::crash::CrashReporter>,
+}
+
+impl PanicHook {
+    /// The `new` method creates a hook that logs panics without writing crash reports.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The `reporter` method also writes a JSON crash report for each panic using `reporter`.
+    #[cfg(feature = "serial")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "serial")))]
+    pub fn reporter(mut self, reporter: crate::crash::CrashReporter) -> Self {
+        self.reporter = Some(reporter);
+        self
+    }
+
+    /// The `install` method replaces the current panic hook.
+    ///
+    /// Panics inside [`catch`] are returned to its caller as errors, so the hook neither logs
+    /// nor reports them.
+    pub fn install(self) {
+        std::panic::set_hook(Box::new(move |info| {
+            let panic = PanicError::from_hook(info);
+            if CATCHING.with(Cell::get) == 0 {
+                log(&panic);
+                #[cfg(feature = "serial")]
+                if let Some(reporter) = &self.reporter {
+                    if let Err(e) = reporter.write(&Bandage::Panic(panic.clone())) {
+                        eprintln!("Could not write crash report: {e}");
+                    }
+                }
+            }
+            LAST_PANIC.with(|last| *last.borrow_mut() = Some(panic));
+        }));
+    }
+}
+
+/// The `install` function replaces the current panic hook with one that logs each panic as a
+/// structured error event, or writes it to standard error without the `trace` feature.
+pub fn install() {
+    PanicHook::new().install();
+}
+
+fn log(panic: &PanicError) {
+    #[cfg(feature = "trace")]
+    tracing::error!(
+        target: "panic",
+        message = %panic.message,
+        location = panic.location.as_deref(),
+        thread = panic.thread.as_deref(),
+        span = panic.span.as_deref(),
+        backtrace = panic.backtrace.as_deref(),
+        "panicked",
+    );
+    #[cfg(not(feature = "trace"))]
+    eprintln!(
+        "panicked: {panic}\n{}",
+        panic.backtrace.as_deref().unwrap_or_default()
+    );
+}
+
+/// The `catch` function runs `f`, returning a [`Bandage::Panic`] if it panics.
+///
+/// When the hook from [`install`] is active, the error includes the location and backtrace of
+/// the panic.
+pub fn catch<F, T>(f: F) -> Clean<T>
+where
+    F: FnOnce() -> T + std::panic::UnwindSafe,
+{
+    LAST_PANIC.with(|last| last.borrow_mut().take());
+    CATCHING.with(|catching| catching.set(catching.get() + 1));
+    let result = std::panic::catch_unwind(f);
+    CATCHING.with(|catching| catching.set(catching.get() - 1));
+    result.map_err(|payload| {
+        let panic = LAST_PANIC
+            .with(|last| last.borrow_mut().take())
+            .unwrap_or_else(|| PanicError::from_payload(payload.as_ref()));
+        Bandage::Panic(panic)
+    })
+}
+
+/// The `catch_clean` function runs a fallible `f`, returning its error or a
+/// [`Bandage::Panic`] if it panics.
+pub fn catch_clean<F, T>(f: F) -> Clean<T>
+where
+    F: FnOnce() -> Clean<T> + std::panic::UnwindSafe,
+{
+    catch(f)?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(error: Bandage) -> String {
+        match error {
+            Bandage::Panic(panic) => panic.message().to_string(),
+            other => panic!("expected a panic, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn catch_returns_the_value_or_the_panic() {
+        assert_eq!(catch(|| 7).unwrap(), 7);
+        assert_eq!(
+            message(catch(|| panic!("borrowed")).unwrap_err()),
+            "borrowed"
+        );
+        let owned = "owned".to_string();
+        assert_eq!(message(catch(|| panic!("{owned}")).unwrap_err()), "owned");
+        assert_eq!(
+            message(catch(|| std::panic::panic_any(7)).unwrap_err()),
+            "panic with a non-string payload"
+        );
+    }
+
+    #[test]
+    fn catch_clean_passes_errors_through() {
+        let error = catch_clean(|| -> Clean<()> { Err(Bandage::Hint("no".to_string())) });
+        assert!(matches!(error, Err(Bandage::Hint(_))));
+        let error = catch_clean(|| -> Clean<()> { panic!("boom") });
+        assert_eq!(message(error.unwrap_err()), "boom");
+    }
+
+    #[test]
+    fn stale_panics_are_not_picked_up() {
+        let stale = PanicError::from_payload(&"stale");
+        LAST_PANIC.with(|last| *last.borrow_mut() = Some(stale));
+        assert_eq!(message(catch(|| panic!("fresh")).unwrap_err()), "fresh");
+        assert!(LAST_PANIC.with(|last| last.borrow().is_none()));
+        assert_eq!(CATCHING.with(Cell::get), 0);
+    }
+
+    /// Installs the hook for the whole process, so every hook check is in this one test.
+    #[cfg(feature = "serial")]
+    #[test]
+    fn hook_records_details_and_reports_uncaught_panics() {
+        let dir = std::env::temp_dir().join(format!("aid-panic-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let reporter = crate::crash::CrashReporter::new("aid-test", "1.0.0").dir(&dir);
+        let previous = std::panic::take_hook();
+        PanicHook::new().reporter(reporter.clone()).install();
+        let caught = catch(|| panic!("caught"));
+        let uncaught = std::thread::Builder::new()
+            .name("doomed".to_string())
+            .spawn(|| panic!("uncaught"))
+            .unwrap()
+            .join();
+        std::panic::set_hook(previous);
+
+        let Err(Bandage::Panic(panic)) = caught else {
+            panic!("expected a panic, got {caught:?}");
+        };
+        assert_eq!(panic.message(), "caught");
+        assert!(panic.location().unwrap().starts_with(file!()));
+        assert!(panic.backtrace().is_some_and(|b| !b.is_empty()));
+        assert!(uncaught.is_err());
+
+        let pending = reporter.pending().unwrap();
+        assert_eq!(pending.len(), 1, "only the uncaught panic is reported");
+        let report = reporter.read(&pending[0]).unwrap();
+        assert!(report.chain[0].contains("uncaught"), "{:?}", report.chain);
+        assert!(report.chain[0].contains("doomed"), "{:?}", report.chain);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,255 @@
+//! The `partial` module holds the results of batch jobs where each record succeeds or fails on
+//! its own, such as geocoding addresses or importing meter reads.
+//!
+//! ```
+//! use aid::prelude::*;
+//!
+//! fn parse_permit(row: &&str) -> Clean<u32> {
+//!     Ok(row.parse()?)
+//! }
+//!
+//! let rows = ["2026-001", "17", "18", "19"];
+//! let results: Partial<u32> = rows.iter().map(parse_permit).collect();
+//! assert_eq!(results.error_rate(), 0.25);
+//! let permits = results.into_result(0.25)?.into_items();
+//! assert_eq!(permits, vec![17, 18, 19]);
+//! # Ok::<(), Bandage>(())
+//! ```
+use crate::prelude::{Bandage, Clean};
+use std::collections::BTreeMap;
+
+/// The `Partial` struct holds the successful items of a batch and the failures, each with the
+/// index or key of the record that failed.
+#[derive(Debug)]
+pub struct Partial<T, K = usize> {
+    items: Vec<T>,
+    failures: Vec<(K, Bandage)>,
+}
+
+impl<T, K> Default for Partial<T, K> {
+    fn default() -> Self {
+        Self {
+            items: Vec::new(),
+            failures: Vec::new(),
+        }
+    }
+}
+
+impl<T, K> Partial<T, K> {
+    /// The `new` method creates an empty batch.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The `push` method adds the result of the record `key`.
+    pub fn push(&mut self, key: K, result: Clean<T>) {
+        match result {
+            Ok(item) => self.items.push(item),
+            Err(e) => self.failures.push((key, e)),
+        }
+    }
+
+    /// The `items` method returns the successful items.
+    pub fn items(&self) -> &[T] {
+        &self.items
+    }
+
+    /// The `failures` method returns the failures with the key of each record.
+    pub fn failures(&self) -> &[(K, Bandage)] {
+        &self.failures
+    }
+
+    /// The `into_items` method returns the successful items, dropping the failures.
+    pub fn into_items(self) -> Vec<T> {
+        self.items
+    }
+
+    /// The `into_parts` method returns the successful items and the failures.
+    pub fn into_parts(self) -> (Vec<T>, Vec<(K, Bandage)>) {
+        (self.items, self.failures)
+    }
+
+    /// The `len` method returns the number of records, successful or not.
+    pub fn len(&self) -> usize {
+        self.items.len() + self.failures.len()
+    }
+
+    /// The `is_empty` method returns `true` if the batch holds no records.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The `is_complete` method returns `true` if no record failed.
+    pub fn is_complete(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// The `error_rate` method returns the fraction of records that failed, or `0.0` for an
+    /// empty batch.
+    pub fn error_rate(&self) -> f64 {
+        if self.is_empty() {
+            0.0
+        } else {
+            self.failures.len() as f64 / self.len() as f64
+        }
+    }
+
+    /// The `summary` method counts the failures by [`Bandage::code`].
+    pub fn summary(&self) -> BTreeMap<&'static str, usize> {
+        let mut summary = BTreeMap::new();
+        for (_, error) in &self.failures {
+            *summary.entry(error.code()).or_insert(0) += 1;
+        }
+        summary
+    }
+
+    /// The `into_result` method returns the batch if the fraction of records that failed is at
+    /// most `threshold`, such as `0.05` for five percent, or a [`Bandage::BatchThreshold`]
+    /// otherwise.
+    pub fn into_result(self, threshold: f64) -> Clean<Self> {
+        if self.error_rate() <= threshold {
+            return Ok(self);
+        }
+        Err(ThresholdError {
+            failed: self.failures.len(),
+            total: self.len(),
+            threshold,
+            summary: self
+                .summary()
+                .into_iter()
+                .map(|(code, count)| (code.to_string(), count))
+                .collect(),
+        }
+        .into())
+    }
+}
+
+impl<T> FromIterator<Clean<T>> for Partial<T> {
+    fn from_iter<I: IntoIterator<Item = Clean<T>>>(iter: I) -> Self {
+        let mut partial = Self::new();
+        for (index, result) in iter.into_iter().enumerate() {
+            partial.push(index, result);
+        }
+        partial
+    }
+}
+
+impl<T, K> FromIterator<(K, Clean<T>)> for Partial<T, K> {
+    fn from_iter<I: IntoIterator<Item = (K, Clean<T>)>>(iter: I) -> Self {
+        let mut partial = Self::new();
+        partial.extend(iter);
+        partial
+    }
+}
+
+impl<T, K> Extend<(K, Clean<T>)> for Partial<T, K> {
+    fn extend<I: IntoIterator<Item = (K, Clean<T>)>>(&mut self, iter: I) {
+        for (key, result) in iter {
+            self.push(key, result);
+        }
+    }
+}
+
+/// The `ThresholdError` struct reports a batch where too many records failed.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub struct ThresholdError {
+    failed: usize,
+    total: usize,
+    threshold: f64,
+    summary: BTreeMap<String, usize>,
+}
+
+impl ThresholdError {
+    /// The `failed` method returns the number of records that failed.
+    pub fn failed(&self) -> usize {
+        self.failed
+    }
+
+    /// The `total` method returns the number of records in the batch.
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// The `threshold` method returns the largest fraction of failures allowed.
+    pub fn threshold(&self) -> f64 {
+        self.threshold
+    }
+
+    /// The `summary` method returns the number of failures by [`Bandage::code`].
+    pub fn summary(&self) -> &BTreeMap<String, usize> {
+        &self.summary
+    }
+}
+
+impl std::fmt::Display for ThresholdError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let rate = self.failed as f64 / self.total.max(1) as f64;
+        write!(
+            f,
+            "{} of {} records failed ({:.1}%), above the {:.1}% threshold",
+            self.failed,
+            self.total,
+            rate * 100.0,
+            self.threshold * 100.0
+        )?;
+        for (i, (code, count)) in self.summary.iter().enumerate() {
+            let sep = if i == 0 { ": " } else { ", " };
+            write!(f, "{sep}{count} {code}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn batch() -> Partial<u32> {
+        ["1", "2", "x", "4", "5", "6", "7", "y"]
+            .iter()
+            .map(|row| Ok(row.parse()?))
+            .chain(std::iter::once(Err(Bandage::Unknown)))
+            .chain(std::iter::once(Ok(10)))
+            .collect()
+    }
+
+    #[test]
+    fn failures_keep_their_index() {
+        let batch = batch();
+        assert_eq!(batch.len(), 10);
+        assert_eq!(batch.items(), [1, 2, 4, 5, 6, 7, 10]);
+        let keys: Vec<_> = batch.failures().iter().map(|(key, _)| *key).collect();
+        assert_eq!(keys, vec![2, 7, 8]);
+        assert_eq!(batch.error_rate(), 0.3);
+    }
+
+    #[test]
+    fn into_result_allows_the_threshold() {
+        let batch = batch().into_result(0.3).unwrap();
+        assert_eq!(batch.into_items().len(), 7);
+        assert!(Partial::<u32>::new()
+            .into_result(0.0)
+            .unwrap()
+            .is_complete());
+    }
+
+    #[test]
+    fn into_result_reports_above_the_threshold() {
+        let error = batch().into_result(0.25).unwrap_err();
+        let Bandage::BatchThreshold(error) = error else {
+            panic!("expected a threshold error, got {error:?}");
+        };
+        assert_eq!((error.failed(), error.total()), (3, 10));
+        assert_eq!(error.threshold(), 0.25);
+        let summary: Vec<_> = error
+            .summary()
+            .iter()
+            .map(|(code, count)| (code.as_str(), *count))
+            .collect();
+        assert_eq!(summary, vec![("int", 2), ("unknown", 1)]);
+        assert_eq!(
+            error.to_string(),
+            "3 of 10 records failed (30.0%), above the 25.0% threshold: 2 int, 1 unknown"
+        );
+    }
+}
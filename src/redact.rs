@@ -0,0 +1,369 @@
+//! The `redact` module scrubs secrets and personal information from error messages before they
+//! are rendered, so connection strings, tokens and email addresses stay out of logs.
+//!
+//! The `Display` and `Debug` output of [`Bandage`](crate::prelude::Bandage), its
+//! [`chain`](crate::prelude::Bandage::chain) and its user-facing messages all pass through
+//! [`redact`].  Set the `AID_REDACT` environment variable to `off` or call
+//! [`set_enabled`]`(false)` to see raw messages during local debugging.
+//!
+//! The rules are regular expressions, so they need the `redact` feature.  Without it, a small
+//! fallback still scrubs URL passwords and the values after names such as `password`, `secret`,
+//! `token` and `Authorization`, but email addresses and other personal information pass through.
+#[cfg(feature = "redact")]
+use crate::prelude::Clean;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::LazyLock;
+#[cfg(feature = "redact")]
+use std::sync::RwLock;
+
+/// The `REDACTED` constant is the text the default rules put in place of a secret.
+pub const REDACTED: &str = "[REDACTED]";
+
+static ENABLED: LazyLock<AtomicBool> = LazyLock::new(|| {
+    let off = std::env::var("AID_REDACT")
+        .map(|v| matches!(v.to_lowercase().as_str(), "0" | "off" | "false" | "no"))
+        .unwrap_or(false);
+    AtomicBool::new(!off)
+});
+
+#[cfg(feature = "redact")]
+static RULES: LazyLock<RwLock<Vec<RedactRule>>> = LazyLock::new(|| RwLock::new(default_rules()));
+
+/// The characters that end a secret value, such as the `&` before the next query parameter.
+#[cfg(feature = "redact")]
+const VALUE: &str = r#"[^&"'\\\s,;}\[\]]"#;
+
+/// The names of parameters that hold a secret.
+#[cfg(feature = "redact")]
+const SECRET_NAMES: &str =
+    "access_token|refresh_token|id_token|client_secret|api_key|apikey|code_verifier|password|secret|token";
+
+/// The `RedactRule` struct replaces every match of a regular expression in a message.
+#[cfg(feature = "redact")]
+#[cfg_attr(docsrs, doc(cfg(feature = "redact")))]
+#[derive(Debug, Clone)]
+pub struct RedactRule {
+    name: String,
+    pattern: regex::Regex,
+    replacement: String,
+}
+
+#[cfg(feature = "redact")]
+impl RedactRule {
+    /// The `new` method creates a rule that replaces matches of `pattern` with `replacement`,
+    /// which may refer to capture groups as in [`regex::Regex::replace_all`].
+    pub fn new(name: &str, pattern: &str, replacement: &str) -> Clean<Self> {
+        Ok(Self {
+            name: name.to_string(),
+            pattern: regex::Regex::new(pattern)?,
+            replacement: replacement.to_string(),
+        })
+    }
+
+    /// The `name` method returns the name of the rule.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// The `default_rules` function returns the built-in rules, which scrub passwords in URLs and
+/// ADO.NET or ODBC connection strings, token and secret parameters, `Authorization` headers,
+/// bearer tokens and email addresses.
+///
+/// A secret is scrubbed whether it is written as `token=...`, as a JSON field or as a label
+/// such as `password: ...`, so prose such as "token: expired" loses its value too.
+#[cfg(feature = "redact")]
+#[cfg_attr(docsrs, doc(cfg(feature = "redact")))]
+pub fn default_rules() -> Vec<RedactRule> {
+    let secret_parameter =
+        format!(r#"(?i)\b({SECRET_NAMES})(\\?"?\s*=\s*\\?"?|\\?"\s*:\s*\\?"?){VALUE}+"#);
+    let secret_label = format!(r#"(?i)\b({SECRET_NAMES})(\s*:\s*["']?){VALUE}+"#);
+    let rules = [
+        (
+            "url-password",
+            r"(?i)\b([a-z][a-z0-9+.-]*://[^:/?#@\s]*:)[^@/?#\s]+@",
+            "${1}[REDACTED]@",
+        ),
+        (
+            "connection-string-password",
+            r#"(?i)\b(password|pwd)(\s*=\s*)("[^"]*"|'[^']*'|\{[^}]*\}|[^;\s"'\\\[]+)"#,
+            "${1}${2}[REDACTED]",
+        ),
+        ("secret-parameter", &secret_parameter, "${1}${2}[REDACTED]"),
+        ("secret-label", &secret_label, "${1}${2}[REDACTED]"),
+        (
+            "authorization-header",
+            r#"(?i)\b(authorization\\?"?\s*[:=]\s*\\?"?(?:bearer\s+|basic\s+|digest\s+)?)[^\s"'\\,;\[]+"#,
+            "${1}[REDACTED]",
+        ),
+        (
+            "bearer-token",
+            r"(?i)\b(bearer\s+)[a-z0-9._~+/-]+=*",
+            "${1}[REDACTED]",
+        ),
+        (
+            "email",
+            r"[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}",
+            "[EMAIL]",
+        ),
+    ];
+    // The tests compile every built-in pattern, so none of these can fail.
+    rules
+        .into_iter()
+        .map(|(name, pattern, replacement)| {
+            RedactRule::new(name, pattern, replacement).expect("built-in redaction rule")
+        })
+        .collect()
+}
+
+/// The `set_enabled` function turns redaction on or off for the whole process.
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// The `is_enabled` function returns whether redaction is on.
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// The `set_rules` function replaces the rule set, such as with [`default_rules`] plus rules
+/// for application-specific secrets.
+#[cfg(feature = "redact")]
+#[cfg_attr(docsrs, doc(cfg(feature = "redact")))]
+pub fn set_rules(rules: Vec<RedactRule>) {
+    if let Ok(mut current) = RULES.write() {
+        *current = rules;
+    }
+}
+
+/// The `add_rule` function appends a rule to the rule set.
+#[cfg(feature = "redact")]
+#[cfg_attr(docsrs, doc(cfg(feature = "redact")))]
+pub fn add_rule(rule: RedactRule) {
+    if let Ok(mut current) = RULES.write() {
+        current.push(rule);
+    }
+}
+
+/// The `scrub` function applies the rule set to `text`, returning it unchanged if redaction is
+/// off.  Without the `redact` feature, it applies the fallback described in the module docs.
+pub fn scrub(text: &str) -> String {
+    if !is_enabled() {
+        return text.to_string();
+    }
+    #[cfg(feature = "redact")]
+    if let Ok(rules) = RULES.read() {
+        return apply(&rules, text);
+    }
+    #[cfg(not(feature = "redact"))]
+    return fallback(text);
+    #[cfg(feature = "redact")]
+    text.to_string()
+}
+
+/// Applies each of `rules` in turn to `text`.
+#[cfg(feature = "redact")]
+fn apply(rules: &[RedactRule], text: &str) -> String {
+    rules.iter().fold(text.to_string(), |text, rule| {
+        rule.pattern
+            .replace_all(&text, rule.replacement.as_str())
+            .into_owned()
+    })
+}
+
+/// The names whose values the fallback scrubs, matched anywhere in a word so `access_token`
+/// and `client_secret` are covered too.
+#[cfg(not(feature = "redact"))]
+const FALLBACK_NAMES: [&str; 6] = ["password", "pwd", "secret", "token", "apikey", "api_key"];
+
+/// The authentication schemes the fallback skips to reach the credential after them.
+#[cfg(not(feature = "redact"))]
+const SCHEMES: [&str; 3] = ["bearer", "basic", "digest"];
+
+/// Scrubs `text` without regular expressions, replacing the password in a URL and the value
+/// after a secret name or `Authorization` followed by `=` or `:`.
+#[cfg(not(feature = "redact"))]
+fn fallback(text: &str) -> String {
+    let lower = text.to_ascii_lowercase();
+    let bytes = lower.as_bytes();
+    let skip = |mut at: usize, set: &[u8]| {
+        while bytes.get(at).is_some_and(|b| set.contains(b)) {
+            at += 1;
+        }
+        at
+    };
+    let value_end = |at: usize| {
+        let mut end = at;
+        while bytes
+            .get(end)
+            .is_some_and(|b| !b"&\"'\\ \t\r\n,;}[]".contains(b))
+        {
+            end += 1;
+        }
+        end
+    };
+    let mut scrubbed = String::with_capacity(text.len());
+    let mut copied = 0;
+    let mut at = 0;
+    while at < bytes.len() {
+        let rest = &bytes[at..];
+        let value = if rest.starts_with(b"://") {
+            // The user information ends at the first `@`, unless a path or space comes first.
+            let start = at + 3;
+            let end = (start..bytes.len())
+                .take_while(|&i| !b"/?# ".contains(&bytes[i]))
+                .find(|&i| bytes[i] == b'@');
+            end.and_then(|end| {
+                let colon = (start..end).find(|&i| bytes[i] == b':')?;
+                Some((colon + 1, end))
+            })
+        } else if let Some(name) = FALLBACK_NAMES
+            .iter()
+            .chain(&["authorization"])
+            .find(|name| rest.starts_with(name.as_bytes()))
+        {
+            let after = skip(at + name.len(), b"\"\\ ");
+            if matches!(bytes.get(after), Some(b'=' | b':')) {
+                let mut start = skip(after + 1, b"\"'\\ ");
+                if let Some(scheme) = SCHEMES
+                    .iter()
+                    .find(|scheme| bytes[start..].starts_with(scheme.as_bytes()))
+                {
+                    if bytes.get(start + scheme.len()) == Some(&b' ') {
+                        start = skip(start + scheme.len(), b" ");
+                    }
+                }
+                Some((start, value_end(start)))
+            } else {
+                None
+            }
+        } else {
+            None
+        };
+        match value {
+            Some((start, end)) if end > start => {
+                scrubbed.push_str(&text[copied..start]);
+                scrubbed.push_str(REDACTED);
+                copied = end;
+                at = end;
+            }
+            _ => at += 1,
+        }
+    }
+    scrubbed.push_str(&text[copied..]);
+    scrubbed
+}
+
+/// The `redact` function renders `value` with `Display` and scrubs the result.
+pub fn redact<T: std::fmt::Display + ?Sized>(value: &T) -> String {
+    scrub(&value.to_string())
+}
+
+/// The `redact_debug` function renders `value` with `Debug` and scrubs the result.
+pub fn redact_debug<T: std::fmt::Debug + ?Sized>(value: &T) -> String {
+    scrub(&format!("{value:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "redact")]
+    fn scrubbed(text: &str) -> String {
+        apply(&default_rules(), text)
+    }
+
+    #[cfg(feature = "redact")]
+    #[test]
+    fn built_in_rules_compile() {
+        let names: Vec<_> = default_rules()
+            .iter()
+            .map(|rule| rule.name().to_string())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "url-password",
+                "connection-string-password",
+                "secret-parameter",
+                "secret-label",
+                "authorization-header",
+                "bearer-token",
+                "email",
+            ]
+        );
+    }
+
+    #[cfg(feature = "redact")]
+    #[test]
+    fn secret_parameters_are_scrubbed() {
+        assert_eq!(
+            scrubbed("GET /cb?code=1&access_token=abc&state=x"),
+            "GET /cb?code=1&access_token=[REDACTED]&state=x"
+        );
+        assert_eq!(
+            scrubbed(r#"{"client_secret": "shh", "scope": "read"}"#),
+            r#"{"client_secret": "[REDACTED]", "scope": "read"}"#
+        );
+        assert_eq!(
+            scrubbed("Server=db;User Id=app;Password=hunter2;"),
+            "Server=db;User Id=app;Password=[REDACTED];"
+        );
+        assert_eq!(
+            scrubbed("postgres://app:hunter2@db/app"),
+            "postgres://app:[REDACTED]@db/app"
+        );
+    }
+
+    #[cfg(feature = "redact")]
+    #[test]
+    fn labelled_secrets_are_scrubbed() {
+        assert_eq!(scrubbed("password: hunter2"), "password: [REDACTED]");
+        assert_eq!(scrubbed("password: letmein"), "password: [REDACTED]");
+        assert_eq!(scrubbed("secret: correcthorse"), "secret: [REDACTED]");
+        assert_eq!(
+            scrubbed("api_key: 'AbCdEfGhIjKlMnOpQr'"),
+            "api_key: '[REDACTED]'"
+        );
+        assert_eq!(scrubbed("token: expired"), "token: [REDACTED]");
+    }
+
+    #[cfg(feature = "redact")]
+    #[test]
+    fn headers_and_emails_are_scrubbed() {
+        assert_eq!(
+            scrubbed("Authorization: Bearer abc.def"),
+            "Authorization: Bearer [REDACTED]"
+        );
+        assert_eq!(scrubbed("sent to jane.doe@example.com"), "sent to [EMAIL]");
+    }
+
+    #[cfg(not(feature = "redact"))]
+    #[test]
+    fn fallback_scrubs_common_secrets() {
+        assert_eq!(
+            fallback("password: letmein, secret=correcthorse"),
+            "password: [REDACTED], secret=[REDACTED]"
+        );
+        assert_eq!(
+            fallback("GET /cb?code=1&access_token=abc&state=x"),
+            "GET /cb?code=1&access_token=[REDACTED]&state=x"
+        );
+        assert_eq!(
+            fallback(r#"{"client_secret": "shh", "scope": "read"}"#),
+            r#"{"client_secret": "[REDACTED]", "scope": "read"}"#
+        );
+        assert_eq!(
+            fallback("Server=db;Pwd=hunter2; postgres://app:hunter2@db/app"),
+            "Server=db;Pwd=[REDACTED]; postgres://app:[REDACTED]@db/app"
+        );
+        assert_eq!(
+            fallback("Authorization: Bearer abc.def"),
+            "Authorization: Bearer [REDACTED]"
+        );
+        assert_eq!(
+            fallback("see https://example.com/a@b"),
+            "see https://example.com/a@b"
+        );
+    }
+}
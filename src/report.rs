@@ -0,0 +1,398 @@
+//! The `report` module writes errors to a CSV file for staff to review in a spreadsheet, one row
+//! per error, streaming each row as it is written so large batch imports stay within memory.
+//!
+//! Errors that hold several problems, such as a [`Bandage::Aggregate`] or a [`Bandage::Config`]
+//! with many issues, are written as a row per problem.
+//!
+//! ```
+//! use aid::prelude::*;
+//!
+//! let errors = vec![Bandage::Hint("parcel 101 has no owner".into()), Bandage::Unknown];
+//! // `ErrorReport::create("import_errors.csv")` writes to a file instead.
+//! let mut report = ErrorReport::new(Vec::new())?;
+//! report.write_all(&errors)?;
+//! assert_eq!(report.rows(), 2);
+//! let csv = report.finish()?;
+//! # Ok::<(), Bandage>(())
+//! ```
+use crate::prelude::{Bandage, Clean, Locale};
+use std::borrow::Cow;
+use std::io::Write;
+use std::path::Path;
+
+/// The `COLUMNS` constant lists the header of the report.
+pub const COLUMNS: [&str; 8] = [
+    "timestamp",
+    "code",
+    "severity",
+    "user_message",
+    "technical_message",
+    "source_file",
+    "record",
+    "field",
+];
+
+/// The `Severity` enum ranks an error for review.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// The operation may succeed if retried.
+    Transient,
+    /// The input or configuration must be corrected.
+    Error,
+    /// The program panicked.
+    Critical,
+}
+
+impl Severity {
+    /// The `of` method returns the severity of `error`.
+    pub fn of(error: &Bandage) -> Self {
+        match error {
+            Bandage::Panic(_) => Self::Critical,
+            e if e.is_retryable() => Self::Transient,
+            _ => Self::Error,
+        }
+    }
+
+    /// The `as_str` method returns the lowercase name of the severity, as written to the report.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Transient => "transient",
+            Self::Error => "error",
+            Self::Critical => "critical",
+        }
+    }
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// The location columns of a row.
+#[derive(Default)]
+struct Location {
+    source_file: String,
+    record: String,
+    field: String,
+}
+
+/// The `ErrorReport` struct writes errors as CSV rows to `W`.
+pub struct ErrorReport<W: Write> {
+    writer: csv::Writer<W>,
+    locale: Locale,
+    rows: u64,
+}
+
+impl ErrorReport<std::fs::File> {
+    /// The `create` method creates or truncates the file at `path` and writes the header.
+    pub fn create<P: AsRef<Path>>(path: P) -> Clean<Self> {
+        Self::new(std::fs::File::create(path)?)
+    }
+}
+
+impl<W: Write> ErrorReport<W> {
+    /// The `new` method writes the header to `writer`, preceded by a byte order mark so Excel
+    /// reads the file as UTF-8.
+    pub fn new(mut writer: W) -> Clean<Self> {
+        writer.write_all("\u{feff}".as_bytes())?;
+        let mut writer = csv::Writer::from_writer(writer);
+        writer.write_record(COLUMNS)?;
+        Ok(Self {
+            writer,
+            locale: Locale::default(),
+            rows: 0,
+        })
+    }
+
+    /// The `locale` method sets the language of the user message column.
+    pub fn locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    /// The `rows` method returns the number of rows written, not counting the header.
+    pub fn rows(&self) -> u64 {
+        self.rows
+    }
+
+    /// The `write` method writes the rows for `error`, taking the source file, record and field
+    /// from the error where it records them.
+    pub fn write(&mut self, error: &Bandage) -> Clean<()> {
+        self.write_rows(None, error)
+    }
+
+    /// The `write_record` method writes the rows for `error` with `record`, such as a row
+    /// number or permit id, in the record column.
+    pub fn write_record<S: ToString>(&mut self, record: S, error: &Bandage) -> Clean<()> {
+        self.write_rows(Some(record.to_string()), error)
+    }
+
+    /// The `write_all` method writes the rows for each error in `errors`.
+    pub fn write_all<'a, I: IntoIterator<Item = &'a Bandage>>(&mut self, errors: I) -> Clean<()> {
+        for error in errors {
+            self.write(error)?;
+        }
+        Ok(())
+    }
+
+    /// The `finish` method flushes the remaining rows and returns the writer.
+    pub fn finish(self) -> Clean<W> {
+        self.writer
+            .into_inner()
+            .map_err(|e| Bandage::from(e.into_error()))
+    }
+
+    fn write_rows(&mut self, record: Option<String>, error: &Bandage) -> Clean<()> {
+        if let Bandage::Aggregate(errors) = error {
+            for error in errors.errors() {
+                self.write_rows(record.clone(), error)?;
+            }
+            return Ok(());
+        }
+        let timestamp = timestamp();
+        let code = error.code();
+        let severity = Severity::of(error).as_str();
+        let user_message = error.user_message_in(self.locale);
+        let technical_message = error.to_string();
+        let mut rows = locations(error);
+        if rows.is_empty() {
+            rows.push((Location::default(), technical_message));
+        }
+        for (mut location, message) in rows {
+            if let Some(record) = &record {
+                location.record = record.clone();
+            }
+            let cells = [
+                Cow::Borrowed(timestamp.as_str()),
+                Cow::Borrowed(code),
+                Cow::Borrowed(severity),
+                cell(&user_message),
+                cell(&message),
+                cell(&location.source_file),
+                cell(&location.record),
+                cell(&location.field),
+            ];
+            self.writer
+                .write_record(cells.iter().map(|c| c.as_bytes()))?;
+            self.rows += 1;
+        }
+        Ok(())
+    }
+}
+
+/// Returns a location and technical message for each problem held by `error`, or none if the
+/// error is a single problem with no recorded location.
+fn locations(error: &Bandage) -> Vec<(Location, String)> {
+    let single = |location| vec![(location, error.to_string())];
+    match error {
+        Bandage::FileName(name) => single(Location {
+            source_file: name.to_string_lossy().into_owned(),
+            ..Default::default()
+        }),
+        Bandage::UserBuild { value } => single(Location {
+            field: value.join(", "),
+            ..Default::default()
+        }),
+        Bandage::Csv(e) => match e.position() {
+            Some(position) => single(Location {
+                record: position.record().to_string(),
+                ..Default::default()
+            }),
+            None => Vec::new(),
+        },
+        #[cfg(feature = "env")]
+        Bandage::EnvVar(e) => single(Location {
+            field: e.name().to_string(),
+            ..Default::default()
+        }),
+        #[cfg(all(feature = "env", feature = "serial"))]
+        Bandage::Config(e) => e
+            .issues()
+            .iter()
+            .map(|issue| {
+                let (source_file, record) = match issue.source() {
+                    Some(crate::config::ConfigSource::File { path, line }) => (
+                        path.display().to_string(),
+                        line.map(|l| format!("line {l}")).unwrap_or_default(),
+                    ),
+                    Some(source) => (source.to_string(), String::new()),
+                    None => (String::new(), String::new()),
+                };
+                let location = Location {
+                    source_file,
+                    record,
+                    field: issue.key().to_string(),
+                };
+                (location, crate::redact::redact(issue))
+            })
+            .collect(),
+        #[cfg(feature = "gis")]
+        Bandage::ShapefileRecord(e) => single(Location {
+            source_file: e.path().display().to_string(),
+            record: e.record_index().map(|i| i.to_string()).unwrap_or_default(),
+            field: e.field().unwrap_or_default().to_string(),
+        }),
+        #[cfg(feature = "gis")]
+        Bandage::GeoJsonFeature(e) => single(Location {
+            record: e.id().map_or_else(|| e.index().to_string(), str::to_string),
+            field: e.pointer().to_string(),
+            ..Default::default()
+        }),
+        #[cfg(feature = "gis")]
+        Bandage::Geometry(e) => single(Location {
+            record: e.feature().unwrap_or_default().to_string(),
+            field: e.location().to_string(),
+            ..Default::default()
+        }),
+        _ => Vec::new(),
+    }
+}
+
+/// Returns `text` with a leading `'` if it starts with a character that makes a spreadsheet
+/// read it as a formula, so a message such as `=HYPERLINK(...)` from an input file stays text.
+fn cell(text: &str) -> Cow<'_, str> {
+    if text.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        Cow::Owned(format!("'{text}"))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// Returns the current time in UTC as an RFC 3339 timestamp, which spreadsheets sort correctly
+/// as text.
+#[cfg(feature = "time")]
+fn timestamp() -> String {
+    jiff::Timestamp::now()
+        .strftime("%Y-%m-%dT%H:%M:%SZ")
+        .to_string()
+}
+
+/// Returns the current time in UTC as an RFC 3339 timestamp, which spreadsheets sort correctly
+/// as text.
+#[cfg(not(feature = "time"))]
+fn timestamp() -> String {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    unix_timestamp(secs)
+}
+
+/// Returns `secs` since the Unix epoch as an RFC 3339 timestamp in UTC, for builds without
+/// `jiff`.
+#[cfg(any(test, not(feature = "time")))]
+fn unix_timestamp(secs: u64) -> String {
+    let (days, rem) = (secs / 86_400, secs % 86_400);
+    // Converts days since the epoch to a civil date, after Howard Hinnant's `civil_from_days`.
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        rem / 3_600,
+        rem % 3_600 / 60,
+        rem % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::AggregateError;
+
+    fn rows(report: ErrorReport<Vec<u8>>) -> Vec<Vec<String>> {
+        let bytes = report.finish().unwrap();
+        let text = std::str::from_utf8(&bytes).unwrap();
+        let text = text.strip_prefix('\u{feff}').expect("byte order mark");
+        let mut reader = csv::Reader::from_reader(text.as_bytes());
+        assert_eq!(reader.headers().unwrap(), COLUMNS.as_slice());
+        reader
+            .records()
+            .map(|row| row.unwrap().iter().map(String::from).collect())
+            .collect()
+    }
+
+    #[test]
+    fn one_row_per_error() {
+        let mut report = ErrorReport::new(Vec::new()).unwrap();
+        report
+            .write_record(17, &Bandage::Hint("no owner".to_string()))
+            .unwrap();
+        report
+            .write(&Bandage::FileName("parcels.csv".into()))
+            .unwrap();
+        assert_eq!(report.rows(), 2);
+        let rows = rows(report);
+        assert!(rows[0][0].ends_with('Z'), "{}", rows[0][0]);
+        assert!(!rows[0][3].is_empty());
+        assert_eq!(rows[0][1..3], ["hint", "error"]);
+        assert_eq!(rows[0][4..], ["Hint: no owner", "", "17", ""]);
+        assert_eq!(rows[1][1..3], ["file_name", "error"]);
+        assert_eq!(rows[1][5], "parcels.csv");
+    }
+
+    #[test]
+    fn aggregate_errors_are_split() {
+        let aggregate = AggregateError::new(vec![
+            Bandage::Hint("first".to_string()),
+            crate::panic::catch(|| panic!("second")).unwrap_err(),
+        ]);
+        let mut report = ErrorReport::new(Vec::new()).unwrap();
+        report.write_record("permit-9", &aggregate.into()).unwrap();
+        let rows = rows(report);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0][1..3], ["hint", "error"]);
+        assert_eq!(rows[1][1..3], ["panic", "critical"]);
+        assert!(rows.iter().all(|row| row[6] == "permit-9"));
+    }
+
+    #[test]
+    fn user_message_follows_the_locale() {
+        let mut report = ErrorReport::new(Vec::new())
+            .unwrap()
+            .locale(Locale::Spanish);
+        report.write(&Bandage::Unknown).unwrap();
+        let rows = rows(report);
+        assert_eq!(
+            rows[0][3],
+            Bandage::Unknown.user_message_in(Locale::Spanish)
+        );
+    }
+
+    #[test]
+    fn formulas_are_written_as_text() {
+        let mut report = ErrorReport::new(Vec::new()).unwrap();
+        report
+            .write_record("=1+1", &Bandage::Hint("bad".to_string()))
+            .unwrap();
+        report
+            .write(&Bandage::UserBuild {
+                value: vec!["@SUM(A1)".to_string()],
+            })
+            .unwrap();
+        report
+            .write_record("-2", &Bandage::FileName("\tparcels.csv".into()))
+            .unwrap();
+        let rows = rows(report);
+        assert_eq!(rows[0][6], "'=1+1");
+        assert_eq!(rows[1][7], "'@SUM(A1)");
+        assert_eq!(rows[2][5], "'\tparcels.csv");
+        assert_eq!(rows[2][6], "'-2");
+        assert_eq!(rows[0][4], "Hint: bad");
+    }
+
+    #[test]
+    fn unix_timestamps_are_civil_dates() {
+        assert_eq!(unix_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(unix_timestamp(951_782_400), "2000-02-29T00:00:00Z");
+        assert_eq!(unix_timestamp(1_772_377_199), "2026-03-01T14:59:59Z");
+        assert_eq!(unix_timestamp(4_102_444_800), "2100-01-01T00:00:00Z");
+    }
+}
@@ -0,0 +1,464 @@
+//! The `shape` module reads shapefiles record by record, annotating failures with the file,
+//! record index, shape type and attribute field, and optionally skipping bad records so one
+//! corrupt parcel does not lose the rest of the file.
+use crate::prelude::{Bandage, Clean};
+use shapefile::dbase::{FieldValue, Record};
+use shapefile::{ReadableShape, Shape, ShapeType};
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+/// The size of the `.shp` and `.shx` file headers, in bytes.
+const HEADER_SIZE: u64 = 100;
+
+/// The `ShapefileError` struct holds a shapefile error with the file and record that failed.
+#[derive(Debug, thiserror::Error)]
+pub struct ShapefileError {
+    path: PathBuf,
+    record: Option<usize>,
+    shape_type: Option<ShapeType>,
+    field: Option<String>,
+    value: Option<String>,
+    #[source]
+    source: Box<dyn std::error::Error + Send + Sync>,
+}
+
+impl ShapefileError {
+    /// The `new` method wraps `source`, a failure reading the shapefile at `path`.
+    ///
+    /// The path is switched to the `.dbf` file for attribute errors and to the `.shx` file for
+    /// index errors, and the field name is taken from the `dbase` error.
+    pub fn new<P: AsRef<Path>>(path: P, source: shapefile::Error) -> Self {
+        let mut path = path.as_ref().to_path_buf();
+        let mut field = None;
+        match &source {
+            shapefile::Error::DbaseError(e) => {
+                path.set_extension("dbf");
+                field = e.field().as_ref().map(|f| f.name().to_string());
+            }
+            shapefile::Error::MissingDbf => {
+                path.set_extension("dbf");
+            }
+            shapefile::Error::MissingIndexFile => {
+                path.set_extension("shx");
+            }
+            _ => {}
+        }
+        Self {
+            path,
+            record: None,
+            shape_type: None,
+            field,
+            value: None,
+            source: Box::new(source),
+        }
+    }
+
+    /// The `record` method sets the zero-based index of the record that failed.
+    pub fn record(mut self, index: usize) -> Self {
+        self.record = Some(index);
+        self
+    }
+
+    /// The `shape_type` method sets the shape type of the record, or of the file if the record
+    /// could not be read.
+    pub fn shape_type(mut self, shape_type: ShapeType) -> Self {
+        self.shape_type = Some(shape_type);
+        self
+    }
+
+    /// The `path` method returns the `.shp`, `.dbf` or `.shx` file that failed.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The `record_index` method returns the zero-based index of the record that failed.
+    pub fn record_index(&self) -> Option<usize> {
+        self.record
+    }
+
+    /// The `record_shape_type` method returns the shape type of the record, or of the file.
+    pub fn record_shape_type(&self) -> Option<ShapeType> {
+        self.shape_type
+    }
+
+    /// The `field` method returns the name of the attribute field that failed.
+    pub fn field(&self) -> Option<&str> {
+        self.field.as_deref()
+    }
+
+    /// The `value` method returns the value of the attribute field that could not be converted.
+    pub fn value(&self) -> Option<&str> {
+        self.value.as_deref()
+    }
+}
+
+impl std::fmt::Display for ShapefileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.path.display())?;
+        if let Some(record) = self.record {
+            write!(f, " record {record}")?;
+        }
+        if let Some(shape_type) = self.shape_type {
+            write!(f, " ({shape_type})")?;
+        }
+        if let Some(field) = &self.field {
+            write!(f, " field '{field}'")?;
+        }
+        if let Some(value) = &self.value {
+            write!(f, " = {value}")?;
+        }
+        write!(f, ": {}", self.source)
+    }
+}
+
+/// The `ShapeRecord` struct is a shape and its attribute record, read by [`ShapefileReader`].
+pub struct ShapeRecord {
+    path: PathBuf,
+    index: usize,
+    shape: Shape,
+    record: Record,
+}
+
+impl std::fmt::Debug for ShapeRecord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ShapeRecord")
+            .field("path", &self.path)
+            .field("index", &self.index)
+            .field("shape_type", &self.shape.shapetype())
+            .finish_non_exhaustive()
+    }
+}
+
+impl ShapeRecord {
+    /// The `index` method returns the zero-based index of the record in the file.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// The `shape` method returns the geometry of the record.
+    pub fn shape(&self) -> &Shape {
+        &self.shape
+    }
+
+    /// The `record` method returns the attributes of the record.
+    pub fn record(&self) -> &Record {
+        &self.record
+    }
+
+    /// The `into_parts` method returns the shape and attribute record.
+    pub fn into_parts(self) -> (Shape, Record) {
+        (self.shape, self.record)
+    }
+
+    /// The `field` method converts the attribute `name` into `T`, naming the file, record,
+    /// field and value if the field is missing or the conversion fails.
+    pub fn field<T>(&self, name: &str) -> Clean<T>
+    where
+        T: TryFrom<FieldValue>,
+        T::Error: std::error::Error + Send + Sync + 'static,
+    {
+        let mut path = self.path.clone();
+        path.set_extension("dbf");
+        let error = |value: Option<String>, source: Box<dyn std::error::Error + Send + Sync>| {
+            ShapefileError {
+                path: path.clone(),
+                record: Some(self.index),
+                shape_type: Some(self.shape.shapetype()),
+                field: Some(name.to_string()),
+                value,
+                source,
+            }
+        };
+        let value = self
+            .record
+            .get(name)
+            .ok_or_else(|| error(None, "the field does not exist".into()))?;
+        T::try_from(value.clone())
+            .map_err(|e| error(Some(format!("{value:?}")), Box::new(e)).into())
+    }
+}
+
+/// The `ShapeBatch` struct holds the records read from a shapefile and the errors from records
+/// that were skipped.
+#[derive(Debug, Default)]
+pub struct ShapeBatch {
+    /// The `records` field holds the records that were read.
+    pub records: Vec<ShapeRecord>,
+    /// The `errors` field holds a [`Bandage::ShapefileRecord`] for each record that was skipped.
+    pub errors: Vec<Bandage>,
+}
+
+/// The `ShapefileReader` struct reads the shapes and attribute records of a shapefile.
+#[derive(Debug, Clone)]
+pub struct ShapefileReader {
+    path: PathBuf,
+    skip_bad_records: bool,
+}
+
+impl ShapefileReader {
+    /// The `new` method creates a reader for the `.shp` file at `path`, with the `.dbf` and
+    /// `.shx` files beside it, that stops at the first bad record.
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            skip_bad_records: false,
+        }
+    }
+
+    /// The `skip_bad_records` method sets whether to collect errors from bad records in the
+    /// [`ShapeBatch`] and keep reading, instead of failing.
+    ///
+    /// Reading still stops at an I/O error, such as a truncated file, and at a record with a
+    /// bad length when there is no `.shx` file, since the records after it cannot be located.
+    pub fn skip_bad_records(mut self, skip: bool) -> Self {
+        self.skip_bad_records = skip;
+        self
+    }
+
+    /// The `read` method reads every record of the shapefile.
+    ///
+    /// Shapes and attribute records are read on their own, so a bad record is skipped along
+    /// with its attributes and the records after it keep their own.
+    pub fn read(&self) -> Clean<ShapeBatch> {
+        let (mut shapes, mut dbf) = self.open()?;
+        let file_type = shapes.shape_type;
+        let mut records = dbf.iter_records();
+        let mut batch = ShapeBatch::default();
+        for index in 0.. {
+            let Some(shape) = shapes.next() else {
+                break;
+            };
+            let result = match (shape, records.next()) {
+                (Ok(shape), Some(Ok(record))) => Ok((shape, record)),
+                (Err(e), _) => Err(e),
+                (Ok(_), Some(Err(e))) => Err(shapefile::Error::DbaseError(e)),
+                (Ok(_), None) => break,
+            };
+            match result {
+                Ok((shape, record)) => batch.records.push(ShapeRecord {
+                    path: self.path.clone(),
+                    index,
+                    shape,
+                    record,
+                }),
+                Err(e) => {
+                    let error = ShapefileError::new(&self.path, e)
+                        .record(index)
+                        .shape_type(file_type);
+                    if !self.skip_bad_records {
+                        return Err(error.into());
+                    }
+                    batch.errors.push(error.into());
+                }
+            }
+        }
+        Ok(batch)
+    }
+
+    /// Opens the `.shp`, `.shx` and `.dbf` files one at a time, so an error names the file
+    /// that failed.
+    fn open(&self) -> Clean<(Shapes, shapefile::dbase::Reader<BufReader<File>>)> {
+        let shp = self.path.with_extension("shp");
+        let shx = self.path.with_extension("shx");
+        let dbf = self.path.with_extension("dbf");
+        let open = |path: &Path| {
+            File::open(path)
+                .map(BufReader::new)
+                .map_err(|e| ShapefileError::new(path, e.into()))
+        };
+        let mut source = open(&shp)?;
+        let header = shapefile::header::Header::read_from(&mut source)
+            .map_err(|e| ShapefileError::new(&shp, e))?;
+        let offsets = if shx.exists() {
+            Some(read_index(open(&shx)?).map_err(|e| ShapefileError::new(&shx, e))?)
+        } else {
+            None
+        };
+        if !dbf.exists() {
+            return Err(ShapefileError::new(&dbf, shapefile::Error::MissingDbf).into());
+        }
+        let records = shapefile::dbase::Reader::new(open(&dbf)?)
+            .map_err(|e| ShapefileError::new(&dbf, e.into()))?;
+        let shapes = Shapes {
+            source,
+            shape_type: header.shape_type,
+            offsets: offsets.map(Vec::into_iter),
+            position: HEADER_SIZE,
+            end: u64::try_from(header.file_length).unwrap_or_default() * 2,
+            done: false,
+        };
+        Ok((shapes, records))
+    }
+}
+
+/// Reads the offset of each record from a `.shx` file.
+fn read_index<R: Read>(mut source: R) -> Result<Vec<u64>, shapefile::Error> {
+    let header = shapefile::header::Header::read_from(&mut source)?;
+    let length = u64::try_from(header.file_length).unwrap_or_default() * 2;
+    let count = length.saturating_sub(HEADER_SIZE) / 8;
+    let mut offsets = Vec::new();
+    for _ in 0..count {
+        let mut entry = [0; 8];
+        source.read_exact(&mut entry)?;
+        let offset = i32::from_be_bytes([entry[0], entry[1], entry[2], entry[3]]);
+        offsets
+            .push(u64::try_from(offset).map_err(|_| shapefile::Error::InvalidShapeRecordSize)? * 2);
+    }
+    Ok(offsets)
+}
+
+/// Reads the shapes of a `.shp` file one record at a time.
+///
+/// Each record is found from the `.shx` offsets, or from the length in the header of the record
+/// before it, and parsed from its own buffer, so a shape that fails to parse leaves the reader
+/// at the start of the next record.
+struct Shapes {
+    source: BufReader<File>,
+    shape_type: ShapeType,
+    offsets: Option<std::vec::IntoIter<u64>>,
+    position: u64,
+    end: u64,
+    done: bool,
+}
+
+impl Shapes {
+    /// Reads the content of the record at `start`, moving to the record after it.
+    fn content(&mut self, start: u64) -> Result<Vec<u8>, shapefile::Error> {
+        self.source.seek(SeekFrom::Start(start))?;
+        let mut header = [0; 8];
+        self.source.read_exact(&mut header)?;
+        let length = i32::from_be_bytes([header[4], header[5], header[6], header[7]]);
+        let length =
+            usize::try_from(length).map_err(|_| shapefile::Error::InvalidShapeRecordSize)? * 2;
+        self.position = start + 8 + length as u64;
+        if self.position > self.end {
+            return Err(shapefile::Error::InvalidShapeRecordSize);
+        }
+        let mut content = vec![0; length];
+        self.source.read_exact(&mut content)?;
+        Ok(content)
+    }
+}
+
+impl Iterator for Shapes {
+    type Item = Result<Shape, shapefile::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let start = match &mut self.offsets {
+            Some(offsets) => offsets.next()?,
+            None if self.position >= self.end => return None,
+            None => self.position,
+        };
+        match self.content(start) {
+            Ok(content) => {
+                let size = i32::try_from(content.len()).unwrap_or(i32::MAX);
+                Some(Shape::read_from(&mut content.as_slice(), size))
+            }
+            Err(e) => {
+                // Without an index, a record that cannot be read hides where the next one
+                // starts.
+                self.done = self.offsets.is_none() || matches!(e, shapefile::Error::IoError(_));
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use shapefile::dbase::{FieldName, TableWriterBuilder};
+    use shapefile::Point;
+
+    /// The size of a point record: the record header, the shape type and two coordinates.
+    const POINT_RECORD: u64 = 8 + 4 + 16;
+
+    /// Writes three points named `a`, `b` and `c` to `name.shp`, then corrupts the shape type
+    /// of the middle record.
+    fn corrupt_middle(name: &str, shx: bool) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aid-shape-{name}-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("parcels.shp");
+        let table =
+            TableWriterBuilder::new().add_character_field(FieldName::try_from("name").unwrap(), 8);
+        let mut writer = shapefile::Writer::from_path(&path, table).unwrap();
+        for (i, name) in ["a", "b", "c"].into_iter().enumerate() {
+            let mut record = Record::default();
+            record.insert("name".to_string(), FieldValue::Character(Some(name.into())));
+            let point = Point::new(i as f64, i as f64);
+            writer.write_shape_and_record(&point, &record).unwrap();
+        }
+        drop(writer);
+        let mut bytes = std::fs::read(&path).unwrap();
+        let shape_type = (HEADER_SIZE + POINT_RECORD + 8) as usize;
+        bytes[shape_type..shape_type + 4].copy_from_slice(&99i32.to_le_bytes());
+        std::fs::write(&path, bytes).unwrap();
+        if !shx {
+            std::fs::remove_file(path.with_extension("shx")).unwrap();
+        }
+        path
+    }
+
+    fn check_skipped(path: &Path) {
+        let batch = ShapefileReader::new(path)
+            .skip_bad_records(true)
+            .read()
+            .unwrap();
+        assert_eq!(batch.errors.len(), 1);
+        let Bandage::ShapefileRecord(error) = &batch.errors[0] else {
+            panic!("expected a shapefile error, got {:?}", batch.errors[0]);
+        };
+        assert_eq!(error.record_index(), Some(1));
+        let read: Vec<_> = batch
+            .records
+            .iter()
+            .map(|record| {
+                let Shape::Point(point) = record.shape() else {
+                    panic!("expected a point");
+                };
+                let name: Option<String> = record.field("name").unwrap();
+                (record.index(), point.x, name.unwrap())
+            })
+            .collect();
+        assert_eq!(read, vec![(0, 0.0, "a".into()), (2, 2.0, "c".into())]);
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn skipped_record_drops_its_attributes() {
+        check_skipped(&corrupt_middle("shx", true));
+    }
+
+    #[test]
+    fn skipped_record_without_index() {
+        check_skipped(&corrupt_middle("no-shx", false));
+    }
+
+    #[test]
+    fn bad_record_fails_without_skipping() {
+        let path = corrupt_middle("strict", true);
+        let error = ShapefileReader::new(&path).read().unwrap_err();
+        let Bandage::ShapefileRecord(error) = error else {
+            panic!("expected a shapefile error, got {error:?}");
+        };
+        assert_eq!(error.record_index(), Some(1));
+        assert_eq!(error.path(), path);
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn missing_dbf_names_the_dbf() {
+        let path = corrupt_middle("no-dbf", true);
+        std::fs::remove_file(path.with_extension("dbf")).unwrap();
+        let error = ShapefileReader::new(&path).read().unwrap_err();
+        let Bandage::ShapefileRecord(error) = error else {
+            panic!("expected a shapefile error, got {error:?}");
+        };
+        assert_eq!(error.path(), path.with_extension("dbf"));
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
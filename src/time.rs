@@ -0,0 +1,416 @@
+//! The `time` module parses dates and times from text using a list of candidate formats, for
+//! inputs such as spreadsheet imports that mix several date formats.
+use crate::prelude::Clean;
+use jiff::civil::{DateTime, Time};
+use jiff::fmt::strtime;
+use jiff::tz::TimeZone;
+use jiff::Zoned;
+
+/// The `DEFAULT_ZONE` constant is the time zone used to interpret civil times by default.
+pub const DEFAULT_ZONE: &str = "America/Los_Angeles";
+
+/// Excel stores dates as days since 1899-12-30, and its largest date is 9999-12-31.
+const EXCEL_MAX_SERIAL: f64 = 2_958_466.0;
+
+/// Excel counts a 1900-02-29 that never happened as serial 60, so earlier serials count from
+/// 1899-12-31 instead.
+const EXCEL_LEAP_BUG_SERIAL: f64 = 60.0;
+
+/// The `DateFormat` enum is a candidate format tried by [`DateParser`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DateFormat {
+    /// A `strftime`-style format string, such as `%m/%d/%Y`.
+    Strtime(String),
+    /// An Excel serial date, the number of days since 1899-12-30 with the time as a fraction.
+    /// Serials before 1900-03-01 are shifted a day to skip Excel's 1900-02-29, and serial 60,
+    /// which is that missing day, is rejected.
+    ExcelSerial,
+}
+
+impl std::fmt::Display for DateFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Strtime(format) => write!(f, "{format}"),
+            Self::ExcelSerial => write!(f, "Excel serial date"),
+        }
+    }
+}
+
+/// The `DateAttempt` struct records why one format failed to parse the input, or why one time
+/// zone could not place a parsed civil time.
+#[derive(Debug)]
+pub struct DateAttempt {
+    format: DateFormat,
+    zone: Option<String>,
+    error: Box<dyn std::error::Error + Send + Sync>,
+}
+
+impl DateAttempt {
+    /// The `format` method returns the format that was tried, or that parsed the input when the
+    /// attempt is for a time zone.
+    pub fn format(&self) -> &DateFormat {
+        &self.format
+    }
+
+    /// The `zone` method returns the time zone that was tried, if the input parsed but could not
+    /// be placed in that zone.
+    pub fn zone(&self) -> Option<&str> {
+        self.zone.as_deref()
+    }
+
+    /// The `error` method returns the error from parsing with this format, usually a
+    /// [`jiff::Error`].
+    pub fn error(&self) -> &(dyn std::error::Error + Send + Sync + 'static) {
+        self.error.as_ref()
+    }
+}
+
+/// The `DateParseError` struct holds the input that failed to parse and the error from every
+/// format tried, or from every time zone tried when the input parsed but no zone could place it.
+#[derive(Debug, thiserror::Error)]
+pub struct DateParseError {
+    input: String,
+    attempts: Vec<DateAttempt>,
+}
+
+impl DateParseError {
+    /// The `input` method returns the text that failed to parse.
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    /// The `attempts` method returns each format or time zone tried and the reason it failed.
+    pub fn attempts(&self) -> &[DateAttempt] {
+        &self.attempts
+    }
+}
+
+impl std::fmt::Display for DateParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let count = self.attempts.len();
+        if self.attempts.iter().any(|attempt| attempt.zone.is_some()) {
+            write!(
+                f,
+                "{:?} could not be placed in any of {count} time zone(s)",
+                self.input
+            )?;
+        } else {
+            write!(f, "{:?} did not match any of {count} format(s)", self.input)?;
+        }
+        for attempt in &self.attempts {
+            match &attempt.zone {
+                Some(zone) => write!(f, "; {} in {zone}: {}", attempt.format, attempt.error)?,
+                None => write!(f, "; {}: {}", attempt.format, attempt.error)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The `DateParser` struct tries a list of formats in order until one parses the input.
+///
+/// By default it accepts `10/17/2026`, `2026-10-17`, `2026-10-17T13:30:00`,
+/// `10/17/2026 13:30` and Excel serial dates, and interprets the result in
+/// [`DEFAULT_ZONE`].
+///
+/// [`DateParser::zoned`] places a civil time in the first of the parser's time zones that knows
+/// the zone name and in which the time happens exactly once, so a time skipped or repeated by a
+/// daylight saving change falls through to the next zone or fails rather than being guessed.
+/// Input parsed with `%z`, `%:z` or `%Q` keeps its own offset or zone.
+#[derive(Debug, Clone)]
+pub struct DateParser {
+    formats: Vec<DateFormat>,
+    zones: Vec<String>,
+}
+
+/// A parsed time, which is civil unless the format read an offset or time zone.
+enum Parsed {
+    Civil(DateTime),
+    Zoned(Zoned),
+}
+
+impl Parsed {
+    /// Returns the civil time, as written in the input.
+    fn datetime(&self) -> DateTime {
+        match self {
+            Self::Civil(datetime) => *datetime,
+            Self::Zoned(zoned) => zoned.datetime(),
+        }
+    }
+}
+
+impl Default for DateParser {
+    fn default() -> Self {
+        let formats = [
+            "%m/%d/%Y",
+            "%Y-%m-%d",
+            "%Y-%m-%dT%H:%M:%S",
+            "%m/%d/%Y %H:%M",
+        ]
+        .into_iter()
+        .map(|f| DateFormat::Strtime(f.to_string()))
+        .chain(std::iter::once(DateFormat::ExcelSerial))
+        .collect();
+        Self {
+            formats,
+            zones: vec![DEFAULT_ZONE.to_string()],
+        }
+    }
+}
+
+impl DateParser {
+    /// The `new` method creates a parser with the default formats and time zone.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The `empty` method creates a parser with no formats, to be filled with
+    /// [`DateParser::format`] and [`DateParser::excel_serial`].
+    pub fn empty() -> Self {
+        Self {
+            formats: Vec::new(),
+            zones: vec![DEFAULT_ZONE.to_string()],
+        }
+    }
+
+    /// The `format` method adds a `strftime`-style format to try after the existing ones.
+    pub fn format<S: Into<String>>(mut self, format: S) -> Self {
+        self.formats.push(DateFormat::Strtime(format.into()));
+        self
+    }
+
+    /// The `excel_serial` method adds Excel serial dates to the formats tried.
+    pub fn excel_serial(mut self) -> Self {
+        self.formats.push(DateFormat::ExcelSerial);
+        self
+    }
+
+    /// The `zone` method sets the IANA time zone used by [`DateParser::zoned`], replacing
+    /// [`DEFAULT_ZONE`] and any other zones.
+    pub fn zone<S: Into<String>>(mut self, zone: S) -> Self {
+        self.zones = vec![zone.into()];
+        self
+    }
+
+    /// The `zones` method sets the IANA time zones tried in order by [`DateParser::zoned`],
+    /// replacing [`DEFAULT_ZONE`].
+    pub fn zones<I, S>(mut self, zones: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.zones = zones.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// The `formats` method returns the formats in the order they are tried.
+    pub fn formats(&self) -> &[DateFormat] {
+        &self.formats
+    }
+
+    /// The `time_zones` method returns the time zones in the order they are tried.
+    pub fn time_zones(&self) -> &[String] {
+        &self.zones
+    }
+
+    /// The `datetime` method parses `input` as a civil datetime, using midnight for formats
+    /// without a time.  Input with an offset or time zone gives the time as written; use
+    /// [`DateParser::zoned`] to keep the instant.
+    pub fn datetime(&self, input: &str) -> Clean<DateTime> {
+        Ok(self.parse(input)?.1.datetime())
+    }
+
+    /// The `date` method parses `input` as a civil date, discarding any time.
+    pub fn date(&self, input: &str) -> Clean<jiff::civil::Date> {
+        Ok(self.datetime(input)?.date())
+    }
+
+    /// The `zoned` method parses `input` and places it in the first of the parser's time zones
+    /// that can, or in the offset or time zone read from the input.
+    pub fn zoned(&self, input: &str) -> Clean<Zoned> {
+        let (format, datetime) = match self.parse(input)? {
+            (_, Parsed::Zoned(zoned)) => return Ok(zoned),
+            (format, Parsed::Civil(datetime)) => (format, datetime),
+        };
+        let mut attempts = Vec::new();
+        for zone in &self.zones {
+            let placed =
+                TimeZone::get(zone).and_then(|tz| tz.to_ambiguous_zoned(datetime).unambiguous());
+            match placed {
+                Ok(zoned) => return Ok(zoned),
+                Err(error) => attempts.push(DateAttempt {
+                    format: format.clone(),
+                    zone: Some(zone.clone()),
+                    error: error.into(),
+                }),
+            }
+        }
+        Err(DateParseError {
+            input: input.trim().to_string(),
+            attempts,
+        }
+        .into())
+    }
+
+    /// Returns the first format that parses `input` and what it parsed.
+    fn parse(&self, input: &str) -> Clean<(&DateFormat, Parsed)> {
+        let input = input.trim();
+        let mut attempts = Vec::new();
+        for format in &self.formats {
+            match Self::attempt(format, input) {
+                Ok(parsed) => return Ok((format, parsed)),
+                Err(error) => attempts.push(DateAttempt {
+                    format: format.clone(),
+                    zone: None,
+                    error,
+                }),
+            }
+        }
+        Err(DateParseError {
+            input: input.to_string(),
+            attempts,
+        }
+        .into())
+    }
+
+    fn attempt(
+        format: &DateFormat,
+        input: &str,
+    ) -> Result<Parsed, Box<dyn std::error::Error + Send + Sync>> {
+        match format {
+            DateFormat::Strtime(format) => {
+                let tm = strtime::parse(format, input)?;
+                if tm.offset().is_some() || tm.iana_time_zone().is_some() {
+                    return Ok(Parsed::Zoned(tm.to_zoned()?));
+                }
+                match tm.to_datetime() {
+                    Ok(datetime) => Ok(Parsed::Civil(datetime)),
+                    Err(_) => Ok(Parsed::Civil(tm.to_date()?.to_datetime(Time::midnight()))),
+                }
+            }
+            DateFormat::ExcelSerial => {
+                let serial = input.parse::<f64>()?;
+                if !(1.0..EXCEL_MAX_SERIAL).contains(&serial) {
+                    return Err(format!("{serial} is outside the range of Excel dates").into());
+                }
+                if serial.trunc() == EXCEL_LEAP_BUG_SERIAL {
+                    return Err(format!("{serial} is 1900-02-29, which does not exist").into());
+                }
+                let mut days = serial.trunc() as i64;
+                if serial < EXCEL_LEAP_BUG_SERIAL {
+                    days += 1;
+                }
+                let seconds = ((serial - serial.trunc()) * 86_400.0).round() as i64;
+                let span = jiff::Span::new().days(days).seconds(seconds);
+                let epoch = jiff::civil::date(1899, 12, 30).to_datetime(Time::midnight());
+                Ok(Parsed::Civil(epoch.checked_add(span)?))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::Bandage;
+    use jiff::civil::date;
+
+    #[test]
+    fn default_formats() {
+        let parser = DateParser::new();
+        let expected = date(2026, 10, 17).at(0, 0, 0, 0);
+        assert_eq!(parser.datetime("10/17/2026").unwrap(), expected);
+        assert_eq!(parser.datetime(" 2026-10-17 ").unwrap(), expected);
+        assert_eq!(
+            parser.datetime("2026-10-17T13:30:00").unwrap(),
+            date(2026, 10, 17).at(13, 30, 0, 0)
+        );
+        assert_eq!(
+            parser.datetime("10/17/2026 13:30").unwrap(),
+            date(2026, 10, 17).at(13, 30, 0, 0)
+        );
+    }
+
+    #[test]
+    fn excel_serials() {
+        let parser = DateParser::empty().excel_serial();
+        let day = |input| parser.date(input).unwrap();
+        assert_eq!(day("1"), date(1900, 1, 1));
+        assert_eq!(day("59"), date(1900, 2, 28));
+        assert_eq!(day("61"), date(1900, 3, 1));
+        assert_eq!(day("46312"), date(2026, 10, 17));
+        assert_eq!(
+            parser.datetime("46312.5625").unwrap(),
+            date(2026, 10, 17).at(13, 30, 0, 0)
+        );
+        assert!(parser.date("60").is_err());
+        assert!(parser.date("60.25").is_err());
+        assert!(parser.date("0").is_err());
+    }
+
+    #[test]
+    fn failure_lists_every_attempt() {
+        let error = DateParser::new().datetime("someday").unwrap_err();
+        let Bandage::DateParse(error) = error else {
+            panic!("expected a date parse error, got {error:?}");
+        };
+        assert_eq!(error.input(), "someday");
+        let formats: Vec<_> = error.attempts().iter().map(DateAttempt::format).collect();
+        assert_eq!(
+            formats,
+            DateParser::new().formats().iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn zoned_uses_the_parser_zone() {
+        let zoned = DateParser::new()
+            .zone("America/New_York")
+            .zoned("2026-10-17T13:30:00")
+            .unwrap();
+        assert_eq!(zoned.time_zone().iana_name(), Some("America/New_York"));
+        assert_eq!(zoned.datetime(), date(2026, 10, 17).at(13, 30, 0, 0));
+    }
+
+    #[test]
+    fn zone_errors_name_the_zone() {
+        let error = DateParser::new()
+            .zones(["Nowhere/Special", "America/New_York"])
+            .zoned("2026-03-08T02:30:00")
+            .unwrap_err();
+        let Bandage::DateParse(error) = error else {
+            panic!("expected a date parse error, got {error:?}");
+        };
+        let zones: Vec<_> = error.attempts().iter().map(DateAttempt::zone).collect();
+        assert_eq!(zones, [Some("Nowhere/Special"), Some("America/New_York")]);
+        assert!(error.to_string().contains("in Nowhere/Special"), "{error}");
+    }
+
+    #[test]
+    fn zones_are_tried_in_order() {
+        // 02:30 is skipped in New York on 2026-03-08, but not in Phoenix.
+        let zoned = DateParser::new()
+            .zones(["America/New_York", "America/Phoenix"])
+            .zoned("2026-03-08T02:30:00")
+            .unwrap();
+        assert_eq!(zoned.time_zone().iana_name(), Some("America/Phoenix"));
+        assert_eq!(DateParser::new().time_zones(), [DEFAULT_ZONE]);
+    }
+
+    #[test]
+    fn parsed_offsets_are_kept() {
+        let parser = DateParser::empty().format("%Y-%m-%d %H:%M %z");
+        let zoned = parser.zoned("2026-03-01 10:00 -0500").unwrap();
+        let expected: jiff::Timestamp = "2026-03-01T15:00:00Z".parse().unwrap();
+        assert_eq!(zoned.timestamp(), expected);
+        assert_eq!(
+            parser.datetime("2026-03-01 10:00 -0500").unwrap(),
+            date(2026, 3, 1).at(10, 0, 0, 0)
+        );
+        let zoned = DateParser::empty()
+            .format("%Y-%m-%d %H:%M %Q")
+            .zoned("2026-03-01 10:00 Europe/Paris")
+            .unwrap();
+        assert_eq!(zoned.time_zone().iana_name(), Some("Europe/Paris"));
+    }
+}
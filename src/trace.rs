@@ -0,0 +1,408 @@
+//! The `trace` module connects [`Bandage`] errors to the `tracing` ecosystem, and installs a
+//! shared subscriber configuration with [`init`].
+use crate::prelude::{Bandage, Clean};
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer, Registry};
+
+/// The `TraceErr` trait logs the error of a [`Clean`] result as a structured `tracing` event and
+/// returns the result unchanged.
+///
+/// Each event records the variant `name`, the `code`, the `message`, the source `chain` and
+/// whether the error is `retryable`.
+pub trait TraceErr {
+    /// The `trace_err` method logs the error, if any, at `level`.
+    fn trace_err(self, level: tracing::Level) -> Self;
+
+    /// The `log_err` method logs the error, if any, at the `ERROR` level.
+    fn log_err(self) -> Self;
+
+    /// The `warn_err` method logs the error, if any, at the `WARN` level.
+    fn warn_err(self) -> Self;
+}
+
+impl<T> TraceErr for Clean<T> {
+    fn trace_err(self, level: tracing::Level) -> Self {
+        if let Err(error) = &self {
+            emit(error, level);
+        }
+        self
+    }
+
+    fn log_err(self) -> Self {
+        self.trace_err(tracing::Level::ERROR)
+    }
+
+    fn warn_err(self) -> Self {
+        self.trace_err(tracing::Level::WARN)
+    }
+}
+
+/// The `emit` function logs `error` as a structured `tracing` event at `level`.
+pub fn emit(error: &Bandage, level: tracing::Level) {
+    let chain = error.chain().join(" <- ");
+    // The level passed to `tracing::event!` must be a constant, so dispatch to each one.
+    macro_rules! event {
+        ($level:expr) => {
+            tracing::event!(
+                $level,
+                name = error.name(),
+                code = error.code(),
+                message = %error,
+                chain = %chain,
+                retryable = error.is_retryable(),
+            )
+        };
+    }
+    match level {
+        tracing::Level::ERROR => event!(tracing::Level::ERROR),
+        tracing::Level::WARN => event!(tracing::Level::WARN),
+        tracing::Level::INFO => event!(tracing::Level::INFO),
+        tracing::Level::DEBUG => event!(tracing::Level::DEBUG),
+        _ => event!(tracing::Level::TRACE),
+    }
+}
+
+/// The `TraceFormat` enum selects how [`init`] formats log lines.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum TraceFormat {
+    /// The default single-line format of `tracing_subscriber`.
+    #[default]
+    Full,
+    /// A compact single-line format.
+    Compact,
+    /// A multi-line format for reading during development.
+    Pretty,
+    /// Newline-delimited JSON for log collectors.
+    Json,
+}
+
+/// The `TraceOptions` struct configures the subscriber installed by [`init`].
+#[derive(Debug, Clone)]
+pub struct TraceOptions {
+    filter: String,
+    format: TraceFormat,
+    file: Option<(PathBuf, String)>,
+    error_layer: bool,
+    ansi: Option<bool>,
+    recent_logs: usize,
+}
+
+impl Default for TraceOptions {
+    fn default() -> Self {
+        Self {
+            filter: "info".to_string(),
+            format: TraceFormat::default(),
+            file: None,
+            error_layer: true,
+            ansi: None,
+            recent_logs: 0,
+        }
+    }
+}
+
+impl TraceOptions {
+    /// The `new` method creates options that log at `info` to stdout in the default format, with
+    /// span traces recorded for errors.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The `crash_reporting` method creates options like [`TraceOptions::new`] that also keep
+    /// the last 200 log lines for crash reports written by the `crash` module.
+    pub fn crash_reporting() -> Self {
+        Self::new().recent_logs(200)
+    }
+
+    /// The `filter` method sets the `env_filter` directives used when `RUST_LOG` is not set,
+    /// such as `"info,sqlx=warn"`.
+    pub fn filter<S: Into<String>>(mut self, directives: S) -> Self {
+        self.filter = directives.into();
+        self
+    }
+
+    /// The `format` method sets the [`TraceFormat`] of log lines.
+    pub fn format(mut self, format: TraceFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// The `file` method also writes logs to files in `dir` named with `prefix` and the date,
+    /// rotated daily, through a non-blocking writer.
+    pub fn file<P: AsRef<Path>, S: Into<String>>(mut self, dir: P, prefix: S) -> Self {
+        self.file = Some((dir.as_ref().to_path_buf(), prefix.into()));
+        self
+    }
+
+    /// The `error_layer` method sets whether to install a [`tracing_error::ErrorLayer`] so span
+    /// traces can be captured with errors.
+    pub fn error_layer(mut self, enabled: bool) -> Self {
+        self.error_layer = enabled;
+        self
+    }
+
+    /// The `ansi` method sets whether lines written to stdout use ANSI colours.  By default they
+    /// do when stdout is a terminal and `NO_COLOR` is not set.  Lines written to a file never do.
+    pub fn ansi(mut self, enabled: bool) -> Self {
+        self.ansi = Some(enabled);
+        self
+    }
+
+    /// The `recent_logs` method sets how many recent log lines to keep for crash reports written
+    /// by the `crash` module, or `0` to keep none.  Defaults to none, or to 200 with
+    /// [`TraceOptions::crash_reporting`].  Needs the `serial` feature.
+    pub fn recent_logs(mut self, capacity: usize) -> Self {
+        self.recent_logs = capacity;
+        self
+    }
+}
+
+/// The `Guard` struct flushes buffered log lines to the log file when dropped, so hold it until
+/// the program exits.
+#[derive(Debug)]
+pub struct Guard {
+    _file: Option<tracing_appender::non_blocking::WorkerGuard>,
+}
+
+type BoxedLayer = Box<dyn Layer<Registry> + Send + Sync>;
+
+fn fmt_layer<W>(format: TraceFormat, writer: W, ansi: bool) -> BoxedLayer
+where
+    W: for<'w> tracing_subscriber::fmt::MakeWriter<'w> + Send + Sync + 'static,
+{
+    let layer = tracing_subscriber::fmt::layer()
+        .with_writer(writer)
+        .with_ansi(ansi);
+    match format {
+        TraceFormat::Full => layer.boxed(),
+        TraceFormat::Compact => layer.compact().boxed(),
+        TraceFormat::Pretty => layer.pretty().boxed(),
+        TraceFormat::Json => layer.json().boxed(),
+    }
+}
+
+/// The `init` function installs a global `tracing` subscriber configured by `options`.
+///
+/// Directives in `RUST_LOG` take precedence over [`TraceOptions::filter`].  Fails if the
+/// directives do not parse or a global subscriber is already set.
+pub fn init(options: TraceOptions) -> Clean<Guard> {
+    let (subscriber, guard) = subscriber(&options, std::io::stdout)?;
+    subscriber.try_init()?;
+    Ok(guard)
+}
+
+/// Builds the subscriber configured by `options`, writing console lines to `stdout`.
+fn subscriber<W>(
+    options: &TraceOptions,
+    stdout: W,
+) -> Clean<(impl tracing::Subscriber + Send + Sync + 'static, Guard)>
+where
+    W: for<'w> tracing_subscriber::fmt::MakeWriter<'w> + Send + Sync + 'static,
+{
+    let filter = match std::env::var(EnvFilter::DEFAULT_ENV) {
+        Ok(directives) => EnvFilter::try_new(directives)?,
+        Err(_) => EnvFilter::try_new(&options.filter)?,
+    };
+    let ansi = options.ansi.unwrap_or_else(|| {
+        std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+    });
+    let mut layers = vec![fmt_layer(options.format, stdout, ansi)];
+    let mut guard = Guard { _file: None };
+    if let Some((dir, prefix)) = &options.file {
+        let appender = tracing_appender::rolling::daily(dir, prefix);
+        let (writer, file_guard) = tracing_appender::non_blocking(appender);
+        layers.push(fmt_layer(options.format, writer, false));
+        guard._file = Some(file_guard);
+    }
+    if options.error_layer {
+        layers.push(tracing_error::ErrorLayer::default().boxed());
+    }
+    #[cfg(feature = "serial")]
+    if options.recent_logs > 0 {
+        layers.push(crate::crash::RecentLogs::new(options.recent_logs).boxed());
+    }
+    let subscriber = tracing_subscriber::registry().with(layers.with_filter(filter));
+    Ok((subscriber, guard))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use std::sync::{Arc, Mutex};
+    use tracing::field::{Field, Visit};
+
+    type Event = (tracing::Level, BTreeMap<String, String>);
+
+    /// A layer that keeps the level and fields of each event.
+    #[derive(Clone, Default)]
+    struct Events(Arc<Mutex<Vec<Event>>>);
+
+    struct Fields(BTreeMap<String, String>);
+
+    impl Visit for Fields {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.0.insert(field.name().to_string(), value.to_string());
+        }
+
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            self.0
+                .insert(field.name().to_string(), format!("{value:?}"));
+        }
+    }
+
+    impl<S: tracing::Subscriber> Layer<S> for Events {
+        fn on_event(
+            &self,
+            event: &tracing::Event<'_>,
+            _ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            let mut fields = Fields(BTreeMap::new());
+            event.record(&mut fields);
+            let level = *event.metadata().level();
+            self.0.lock().unwrap().push((level, fields.0));
+        }
+    }
+
+    fn events(f: impl FnOnce()) -> Vec<Event> {
+        let events = Events::default();
+        let subscriber = Registry::default().with(events.clone());
+        tracing::subscriber::with_default(subscriber, f);
+        let events = events.0.lock().unwrap();
+        events.clone()
+    }
+
+    #[test]
+    fn errors_are_logged_with_their_fields() {
+        let url = "postgres://app:hunter2@db/app";
+        let events = events(|| {
+            let result: Clean<()> = Err(Bandage::Hint(format!("could not reach {url}")));
+            assert!(result.log_err().is_err());
+            assert_eq!(Ok(1).warn_err().unwrap(), 1);
+        });
+        let [(level, fields)] = events.as_slice() else {
+            panic!("expected one event, got {events:?}");
+        };
+        assert_eq!(*level, tracing::Level::ERROR);
+        assert_eq!(fields["name"], "Hint");
+        assert_eq!(fields["code"], "hint");
+        assert_eq!(fields["retryable"], "false");
+        assert!(fields["message"].starts_with("Hint: could not reach"));
+        assert!(fields["chain"].starts_with("Hint: could not reach"));
+        #[cfg(feature = "redact")]
+        for field in ["message", "chain"] {
+            assert!(!fields[field].contains("hunter2"), "{}", fields[field]);
+        }
+    }
+
+    #[test]
+    fn trace_err_uses_the_level() {
+        let levels = [
+            tracing::Level::ERROR,
+            tracing::Level::WARN,
+            tracing::Level::INFO,
+            tracing::Level::DEBUG,
+            tracing::Level::TRACE,
+        ];
+        let events = events(|| {
+            for level in levels {
+                let _ = Err::<(), _>(Bandage::Unknown).trace_err(level);
+            }
+        });
+        let logged: Vec<_> = events.iter().map(|(level, _)| *level).collect();
+        assert_eq!(logged, levels);
+    }
+
+    #[test]
+    fn file_lines_are_filtered_and_plain() {
+        let dir = std::env::temp_dir().join(format!("aid-trace-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let options = TraceOptions::new()
+            .format(TraceFormat::Json)
+            .file(&dir, "app.log")
+            .ansi(true);
+        let (subscriber, guard) = subscriber(&options, std::io::sink).unwrap();
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!(parcel = 42, "imported");
+            tracing::debug!("hidden");
+        });
+        drop(guard);
+        let file = std::fs::read_dir(&dir).unwrap().next().unwrap().unwrap();
+        let text = std::fs::read_to_string(file.path()).unwrap();
+        let lines: Vec<serde_json::Value> = text
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 1, "{text}");
+        assert_eq!(lines[0]["level"], "INFO");
+        assert_eq!(lines[0]["fields"]["message"], "imported");
+        assert_eq!(lines[0]["fields"]["parcel"], 42);
+        assert!(!text.contains('\u{1b}'));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn colours_follow_the_option() {
+        let lines = |ansi| {
+            let buffer = Arc::new(Mutex::new(Vec::new()));
+            let writer = buffer.clone();
+            let make_writer = move || Buffer(writer.clone());
+            let options = TraceOptions::new().ansi(ansi).format(TraceFormat::Compact);
+            let (subscriber, _guard) = subscriber(&options, make_writer).unwrap();
+            tracing::subscriber::with_default(subscriber, || tracing::warn!("careful"));
+            let bytes = buffer.lock().unwrap().clone();
+            String::from_utf8(bytes).unwrap()
+        };
+        assert!(lines(true).contains('\u{1b}'));
+        let plain = lines(false);
+        assert!(
+            plain.contains("WARN") && !plain.contains('\u{1b}'),
+            "{plain}"
+        );
+    }
+
+    /// A writer into a shared buffer.
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl std::io::Write for Buffer {
+        fn write(&mut self, bytes: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(bytes);
+            Ok(bytes.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn bad_directives_fail() {
+        let options = TraceOptions::new().filter("aid=loudly");
+        let error = subscriber(&options, std::io::sink).err().unwrap();
+        assert!(matches!(error, Bandage::TraceFilter(_)), "{error:?}");
+    }
+
+    #[cfg(feature = "serial")]
+    #[test]
+    fn crash_reporting_keeps_recent_logs() {
+        let options = TraceOptions::crash_reporting();
+        let (subscriber, _guard) = subscriber(&options, std::io::sink).unwrap();
+        tracing::subscriber::with_default(subscriber, || tracing::info!("kept for crash reports"));
+        let reporter = crate::crash::CrashReporter::new("aid-test", "1.0.0");
+        let logs = reporter.report(&Bandage::Unknown).logs;
+        assert!(
+            logs.iter()
+                .any(|line| line.ends_with("kept for crash reports")),
+            "{logs:?}"
+        );
+    }
+
+    #[test]
+    fn init_installs_one_global_subscriber() {
+        let options = TraceOptions::new().filter("off").ansi(false);
+        let _guard = init(options.clone()).unwrap();
+        assert!(matches!(init(options), Err(Bandage::TraceInit(_))));
+    }
+}
@@ -0,0 +1,528 @@
+//! The `ui` module provides Dioxus components for showing a [`Bandage`] in a desktop app: a modal
+//! dialog for errors that stop the user, a queue of toasts for errors that do not, and a
+//! boundary that catches [`Clean`](crate::prelude::Clean) failures from event handlers.
+//!
+//! Wrap the app in an [`ErrorProvider`], then push errors with the [`ErrorQueue`] from
+//! [`use_error_queue`], or return them as a [`BoundaryError`] from event handlers inside a
+//! [`BandageBoundary`].
+//!
+//! ```no_run
+//! use aid::prelude::*;
+//! use aid::ui::{BandageBoundary, ErrorProvider};
+//! use dioxus::prelude::*;
+//!
+//! fn save() -> Clean<()> {
+//!     Err(Bandage::Hint("the disk is full".to_string()))
+//! }
+//!
+//! #[allow(non_snake_case)]
+//! fn App() -> Element {
+//!     rsx! {
+//!         ErrorProvider {
+//!             BandageBoundary {
+//!                 button {
+//!                     onclick: move |_| {
+//!                         save().map_err(BoundaryError::from)?;
+//!                         Ok(())
+//!                     },
+//!                     "Save"
+//!                 }
+//!             }
+//!         }
+//!     }
+//! }
+//! ```
+use crate::prelude::{Bandage, Locale};
+use crate::redact::redact;
+use dioxus::prelude::*;
+use dioxus::CapturedError;
+use std::cell::{Cell, RefCell};
+use std::rc::{Rc, Weak};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Precedes the id of a [`BoundaryError`] in its `Debug` output.
+const MARKER: &str = "aid::BoundaryError#";
+
+thread_local! {
+    /// The errors of the live [`BoundaryError`]s on this thread, by id.
+    static THROWN: RefCell<Vec<(u64, Weak<Bandage>)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// The `BoundaryError` struct wraps a [`Bandage`] returned from an event handler or component
+/// inside a [`BandageBoundary`], so the boundary can show its user-facing message.
+///
+/// Dioxus 0.6 cannot downcast a `CapturedError` to the error it holds, so the boundary finds the
+/// error by the id this type writes in its `Debug` output.  A `Bandage` returned without this
+/// wrapper is shown as an unknown error with its technical message.
+pub struct BoundaryError {
+    id: u64,
+    error: Rc<Bandage>,
+}
+
+impl BoundaryError {
+    /// The `error` method returns the wrapped error.
+    pub fn error(&self) -> &Bandage {
+        &self.error
+    }
+
+    /// Returns the error of the live `BoundaryError` whose `Debug` output appears in `text`.
+    fn find(text: &str) -> Option<Rc<Bandage>> {
+        let (_, rest) = text.split_once(MARKER)?;
+        let end = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let id = rest[..end].parse::<u64>().ok()?;
+        THROWN.with(|thrown| {
+            thrown
+                .borrow()
+                .iter()
+                .find(|(i, _)| *i == id)
+                .and_then(|(_, error)| error.upgrade())
+        })
+    }
+}
+
+impl From<Bandage> for BoundaryError {
+    fn from(error: Bandage) -> Self {
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        let error = Rc::new(error);
+        THROWN.with(|thrown| {
+            let mut thrown = thrown.borrow_mut();
+            thrown.retain(|(_, error)| error.strong_count() > 0);
+            thrown.push((id, Rc::downgrade(&error)));
+        });
+        Self { id, error }
+    }
+}
+
+impl std::fmt::Debug for BoundaryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{MARKER}{} {:?}", self.id, self.error)
+    }
+}
+
+impl std::fmt::Display for BoundaryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.error.fmt(f)
+    }
+}
+
+impl std::error::Error for BoundaryError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.error.source()
+    }
+}
+
+/// The `ErrorNotice` struct is an error prepared for display, with the user-facing message and
+/// the technical chain captured when it was pushed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorNotice {
+    id: u64,
+    name: String,
+    code: String,
+    message: String,
+    chain: Vec<String>,
+    boundary: Option<ErrorContext>,
+}
+
+impl ErrorNotice {
+    /// The `new` method prepares `error` for display with the user-facing message in `locale`.
+    pub fn new(error: &Bandage, locale: Locale) -> Self {
+        Self {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            name: error.name().to_string(),
+            code: error.code().to_string(),
+            message: error.user_message_in(locale),
+            chain: error.chain(),
+            boundary: None,
+        }
+    }
+
+    fn from_captured(error: &CapturedError, locale: Locale) -> Self {
+        if let Some(error) = error.downcast::<BoundaryError>() {
+            return Self::new(error.error(), locale);
+        }
+        if let Some(error) = error.downcast::<Bandage>() {
+            return Self::new(error, locale);
+        }
+        let display = error.to_string();
+        if let Some(error) = BoundaryError::find(&display) {
+            return Self::new(&error, locale);
+        }
+        let mut notice = Self::new(&Bandage::Unknown, locale);
+        let first = display.lines().next().unwrap_or_default();
+        notice.chain = vec![redact(first.trim_start_matches("Encountered error: "))];
+        notice
+    }
+
+    /// The `id` method returns the identifier of the notice, unique within the process.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// The `name` method returns the [`Bandage::name`] of the error.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The `code` method returns the [`Bandage::code`] of the error.
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    /// The `message` method returns the user-facing message.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// The `chain` method returns the [`Bandage::chain`] of the error.
+    pub fn chain(&self) -> &[String] {
+        &self.chain
+    }
+
+    /// The `details` method returns the code, message and chain as plain text, for pasting into
+    /// a support request.
+    pub fn details(&self) -> String {
+        let mut details = format!("{} ({})\n{}\n", self.name, self.code, self.message);
+        for (i, link) in self.chain.iter().enumerate() {
+            details.push_str(&format!("\n{}. {link}", i + 1));
+        }
+        details
+    }
+}
+
+/// The `ErrorQueue` struct holds the pending dialogs and toasts of an [`ErrorProvider`].  It is
+/// `Copy`, so move it into event handlers and futures freely.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ErrorQueue {
+    dialogs: Signal<Vec<ErrorNotice>>,
+    toasts: Signal<Vec<ErrorNotice>>,
+    locale: Signal<Locale>,
+    max_toasts: Signal<usize>,
+}
+
+impl ErrorQueue {
+    /// The `report` method shows `error` in a modal dialog, after any dialogs already open.
+    pub fn report(&self, error: Bandage) {
+        let notice = ErrorNotice::new(&error, *self.locale.peek());
+        self.push_dialog(notice);
+    }
+
+    /// The `toast` method shows `error` in a toast, dropping the oldest toast past the limit of
+    /// the provider.
+    pub fn toast(&self, error: Bandage) {
+        let notice = ErrorNotice::new(&error, *self.locale.peek());
+        let max = *self.max_toasts.peek();
+        let mut toasts = self.toasts;
+        let mut toasts = toasts.write();
+        toasts.push(notice);
+        while toasts.len() > max.max(1) {
+            toasts.remove(0);
+        }
+    }
+
+    /// The `report_err` method shows the error of `result` in a dialog and returns the value, if
+    /// any.
+    pub fn report_err<T>(&self, result: crate::prelude::Clean<T>) -> Option<T> {
+        result.map_err(|e| self.report(e)).ok()
+    }
+
+    /// The `toast_err` method shows the error of `result` in a toast and returns the value, if
+    /// any.
+    pub fn toast_err<T>(&self, result: crate::prelude::Clean<T>) -> Option<T> {
+        result.map_err(|e| self.toast(e)).ok()
+    }
+
+    /// The `dismiss` method closes the dialog or toast with the given [`ErrorNotice::id`].
+    ///
+    /// Dismissing the last dialog from a [`BandageBoundary`] renders its children again.
+    pub fn dismiss(&self, id: u64) {
+        let mut toasts = self.toasts;
+        toasts.write().retain(|notice| notice.id != id);
+        let mut dialogs = self.dialogs;
+        let removed = {
+            let mut dialogs = dialogs.write();
+            let index = dialogs.iter().position(|notice| notice.id == id);
+            index.map(|i| dialogs.remove(i))
+        };
+        if let Some(boundary) = removed.and_then(|notice| notice.boundary) {
+            let pending = dialogs
+                .peek()
+                .iter()
+                .any(|notice| notice.boundary.as_ref() == Some(&boundary));
+            if !pending {
+                boundary.clear_errors();
+            }
+        }
+    }
+
+    /// The `dialogs` method returns the pending dialogs, the first of which is shown.
+    pub fn dialogs(&self) -> Vec<ErrorNotice> {
+        self.dialogs.read().clone()
+    }
+
+    /// The `toasts` method returns the toasts shown, oldest first.
+    pub fn toasts(&self) -> Vec<ErrorNotice> {
+        self.toasts.read().clone()
+    }
+
+    /// The `locale` method returns the locale of the user-facing messages.
+    pub fn locale(&self) -> Locale {
+        *self.locale.read()
+    }
+
+    fn push_dialog(&self, notice: ErrorNotice) {
+        let mut dialogs = self.dialogs;
+        dialogs.write().push(notice);
+    }
+}
+
+/// The `use_error_queue` hook returns the [`ErrorQueue`] of the nearest [`ErrorProvider`].
+///
+/// Panics if called outside an `ErrorProvider`.
+pub fn use_error_queue() -> ErrorQueue {
+    use_context::<ErrorQueue>()
+}
+
+/// The `ErrorProvider` component provides an [`ErrorQueue`] to its children, and shows the
+/// [`ErrorDialog`] and [`ErrorToasts`] for it.
+#[component]
+pub fn ErrorProvider(
+    /// The app, or the part of it that reports errors.
+    children: Element,
+    /// The locale of the user-facing messages.
+    #[props(default)]
+    locale: Locale,
+    /// The number of toasts shown at once.
+    #[props(default = 3)]
+    max_toasts: usize,
+) -> Element {
+    let queue = use_context_provider(|| ErrorQueue {
+        dialogs: Signal::new(Vec::new()),
+        toasts: Signal::new(Vec::new()),
+        locale: Signal::new(locale),
+        max_toasts: Signal::new(max_toasts),
+    });
+    use_effect(use_reactive!(|locale, max_toasts| {
+        let (mut current_locale, mut current_max) = (queue.locale, queue.max_toasts);
+        current_locale.set(locale);
+        current_max.set(max_toasts);
+    }));
+    rsx! {
+        {children}
+        ErrorDialog {}
+        ErrorToasts {}
+    }
+}
+
+/// The `ErrorDialog` component shows the first pending dialog of the [`ErrorQueue`] as a modal,
+/// with the technical chain behind an expandable section and a button to copy the details.
+///
+/// The [`ErrorProvider`] renders one already.
+#[component]
+pub fn ErrorDialog() -> Element {
+    let queue = use_error_queue();
+    let dialogs = queue.dialogs.read();
+    let Some(notice) = dialogs.first() else {
+        return rsx! {};
+    };
+    let labels = Labels::new(queue.locale());
+    let id = notice.id;
+    let details = notice.details();
+    rsx! {
+        div {
+            class: "aid-error-overlay",
+            style: "position: fixed; inset: 0; display: flex; align-items: center; justify-content: center; background: rgba(0, 0, 0, 0.4); z-index: 1000;",
+            div {
+                class: "aid-error-dialog",
+                role: "alertdialog",
+                aria_modal: "true",
+                style: "max-width: 32rem; padding: 1.5rem; border-radius: 0.5rem; background: white; color: black; box-shadow: 0 0.5rem 2rem rgba(0, 0, 0, 0.3);",
+                h2 { "{labels.title}" }
+                p { "{notice.message}" }
+                details {
+                    summary { "{labels.details}" }
+                    ol {
+                        style: "font-family: monospace; white-space: pre-wrap;",
+                        for link in notice.chain.iter() {
+                            li { "{link}" }
+                        }
+                    }
+                }
+                div {
+                    style: "display: flex; gap: 0.5rem; justify-content: flex-end;",
+                    button {
+                        onclick: move |_| {
+                            copy(&details);
+                        },
+                        "{labels.copy}"
+                    }
+                    button { onclick: move |_| queue.dismiss(id), "{labels.dismiss}" }
+                }
+            }
+        }
+    }
+}
+
+/// The `ErrorToasts` component shows the toasts of the [`ErrorQueue`], each with a button to
+/// dismiss it.
+///
+/// The [`ErrorProvider`] renders one already.
+#[component]
+pub fn ErrorToasts() -> Element {
+    let queue = use_error_queue();
+    let labels = Labels::new(queue.locale());
+    rsx! {
+        div {
+            class: "aid-error-toasts",
+            role: "status",
+            style: "position: fixed; right: 1rem; bottom: 1rem; display: flex; flex-direction: column; gap: 0.5rem; z-index: 999;",
+            for notice in queue.toasts.read().iter() {
+                div {
+                    key: "{notice.id}",
+                    class: "aid-error-toast",
+                    title: "{notice.code}",
+                    style: "display: flex; gap: 1rem; align-items: center; padding: 0.75rem 1rem; border-radius: 0.5rem; background: #333; color: white;",
+                    span { "{notice.message}" }
+                    button {
+                        onclick: {
+                            let id = notice.id;
+                            move |_| queue.dismiss(id)
+                        },
+                        aria_label: "{labels.dismiss}",
+                        "×"
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The `BandageBoundary` component catches errors returned from the event handlers and
+/// components below it and reports them to the [`ErrorQueue`] as dialogs.
+///
+/// While a dialog from the boundary is open, the boundary shows `fallback` in place of its
+/// children, which render again once the dialogs are dismissed.
+#[component]
+pub fn BandageBoundary(
+    /// The part of the app to guard.
+    children: Element,
+    /// Shown in place of the children until the errors are dismissed.
+    #[props(default = VNode::empty())]
+    fallback: Element,
+) -> Element {
+    let queue = use_error_queue();
+    let boundary = use_hook(provide_error_boundary);
+    let forwarded = use_hook(|| Rc::new(Cell::new(0)));
+    let errors = boundary.errors();
+    if errors.is_empty() {
+        forwarded.set(0);
+        return rsx! {
+            {children}
+        };
+    }
+    let locale = *queue.locale.peek();
+    let notices: Vec<ErrorNotice> = errors
+        .iter()
+        .skip(forwarded.get())
+        .map(|error| ErrorNotice {
+            boundary: Some(boundary.clone()),
+            ..ErrorNotice::from_captured(error, locale)
+        })
+        .collect();
+    forwarded.set(errors.len());
+    if !notices.is_empty() {
+        // Signals should not be written while rendering, so report from a task.
+        spawn(async move {
+            for notice in notices {
+                queue.push_dialog(notice);
+            }
+        });
+    }
+    rsx! {
+        {fallback}
+    }
+}
+
+/// Copies `text` to the clipboard through the webview.
+fn copy(text: &str) {
+    let mut literal = String::with_capacity(text.len() + 2);
+    literal.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => literal.push_str("\\\""),
+            '\\' => literal.push_str("\\\\"),
+            '\n' => literal.push_str("\\n"),
+            '\r' => literal.push_str("\\r"),
+            c if c.is_control() || c == '\u{2028}' || c == '\u{2029}' => {
+                literal.push_str(&format!("\\u{:04x}", c as u32))
+            }
+            c => literal.push(c),
+        }
+    }
+    literal.push('"');
+    document::eval(&format!("navigator.clipboard.writeText({literal});"));
+}
+
+/// Labels of the components in a locale.
+struct Labels {
+    title: &'static str,
+    details: &'static str,
+    copy: &'static str,
+    dismiss: &'static str,
+}
+
+impl Labels {
+    fn new(locale: Locale) -> Self {
+        match locale {
+            Locale::English => Self {
+                title: "Something went wrong",
+                details: "Technical details",
+                copy: "Copy details",
+                dismiss: "Dismiss",
+            },
+            Locale::Spanish => Self {
+                title: "Algo salió mal",
+                details: "Detalles técnicos",
+                copy: "Copiar detalles",
+                dismiss: "Cerrar",
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn boundary_error_is_recovered() {
+        let error = BoundaryError::from(Bandage::FileName("parcels.shp".into()));
+        let captured = CapturedError::new(error);
+        let notice = ErrorNotice::from_captured(&captured, Locale::Spanish);
+        assert_eq!(notice.name(), "FileName");
+        assert_eq!(
+            notice.message(),
+            "El nombre de archivo \"parcels.shp\" no es válido."
+        );
+    }
+
+    #[test]
+    fn dropped_boundary_error_is_forgotten() {
+        let error = BoundaryError::from(Bandage::Unknown);
+        let text = format!("{error:?}");
+        assert!(BoundaryError::find(&text).is_some());
+        drop(error);
+        assert!(BoundaryError::find(&text).is_none());
+    }
+
+    #[test]
+    fn bare_bandage_is_unknown() {
+        let captured = CapturedError::new(Bandage::Hint("disk full".to_string()));
+        let notice = ErrorNotice::from_captured(&captured, Locale::English);
+        assert_eq!(notice.code(), Bandage::Unknown.code());
+        assert!(notice.chain()[0].contains("disk full"));
+    }
+}
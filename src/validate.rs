@@ -0,0 +1,334 @@
+//! The `validate` module describes invalid form and API input field by field, so front ends can
+//! show each problem next to its field.
+//!
+//! ```
+//! use aid::prelude::*;
+//!
+//! let zip = "9752";
+//! let mut errors = ValidationError::new();
+//! if zip.len() != 5 {
+//!     errors.push(Violation::pattern("address.zip", r"^\d{5}$"));
+//! }
+//! assert!(errors.into_result().is_err());
+//! ```
+//!
+//! With the `serial` feature the error serializes to JSON, and with the `route` feature it
+//! renders as a `422 Unprocessable Entity` response with the JSON as its body.
+use crate::prelude::Clean;
+use std::collections::BTreeMap;
+
+/// The `Constraint` enum is the rule a field violated.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Constraint {
+    /// The field is required but missing or empty.
+    Required,
+    /// The field is longer than the `max` parameter.
+    TooLong,
+    /// The field is shorter than the `min` parameter.
+    TooShort,
+    /// The field does not match the `pattern` parameter.
+    Pattern,
+    /// The field is outside the `min` and `max` parameters.
+    Range,
+    /// An application-defined rule, by code.
+    Other(String),
+}
+
+impl Constraint {
+    /// The `code` method returns the snake case code of the constraint, such as `"too_long"`.
+    pub fn code(&self) -> &str {
+        match self {
+            Self::Required => "required",
+            Self::TooLong => "too_long",
+            Self::TooShort => "too_short",
+            Self::Pattern => "pattern",
+            Self::Range => "range",
+            Self::Other(code) => code,
+        }
+    }
+}
+
+impl std::fmt::Display for Constraint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+#[cfg(any(feature = "serial", feature = "route"))]
+impl serde::Serialize for Constraint {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.code())
+    }
+}
+
+/// The `Violation` struct is one field that broke one constraint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(any(feature = "serial", feature = "route"), derive(serde::Serialize))]
+pub struct Violation {
+    path: String,
+    #[cfg_attr(any(feature = "serial", feature = "route"), serde(rename = "code"))]
+    constraint: Constraint,
+    #[cfg_attr(
+        any(feature = "serial", feature = "route"),
+        serde(skip_serializing_if = "BTreeMap::is_empty")
+    )]
+    params: BTreeMap<String, String>,
+    #[cfg_attr(
+        any(feature = "serial", feature = "route"),
+        serde(skip_serializing_if = "Option::is_none")
+    )]
+    message: Option<String>,
+}
+
+impl Violation {
+    /// The `new` method creates a violation of `constraint` by the field at `path`, such as
+    /// `address.zip` or `items[3].qty`.
+    pub fn new<S: Into<String>>(path: S, constraint: Constraint) -> Self {
+        Self {
+            path: path.into(),
+            constraint,
+            params: BTreeMap::new(),
+            message: None,
+        }
+    }
+
+    /// The `required` method creates a [`Constraint::Required`] violation.
+    pub fn required<S: Into<String>>(path: S) -> Self {
+        Self::new(path, Constraint::Required)
+    }
+
+    /// The `too_long` method creates a [`Constraint::TooLong`] violation with the `max` length.
+    pub fn too_long<S: Into<String>>(path: S, max: usize) -> Self {
+        Self::new(path, Constraint::TooLong).param("max", max)
+    }
+
+    /// The `too_short` method creates a [`Constraint::TooShort`] violation with the `min`
+    /// length.
+    pub fn too_short<S: Into<String>>(path: S, min: usize) -> Self {
+        Self::new(path, Constraint::TooShort).param("min", min)
+    }
+
+    /// The `pattern` method creates a [`Constraint::Pattern`] violation with the `pattern` the
+    /// field must match.
+    pub fn pattern<S: Into<String>, P: std::fmt::Display>(path: S, pattern: P) -> Self {
+        Self::new(path, Constraint::Pattern).param("pattern", pattern)
+    }
+
+    /// The `range` method creates a [`Constraint::Range`] violation with the `min` and `max`
+    /// values allowed.
+    pub fn range<S: Into<String>, N: std::fmt::Display>(path: S, min: N, max: N) -> Self {
+        Self::new(path, Constraint::Range)
+            .param("min", min)
+            .param("max", max)
+    }
+
+    /// The `param` method adds a parameter of the constraint, such as the `max` length.
+    pub fn param<K: Into<String>, V: std::fmt::Display>(mut self, key: K, value: V) -> Self {
+        self.params.insert(key.into(), value.to_string());
+        self
+    }
+
+    /// The `message` method sets a message for the field, shown in place of one derived from
+    /// the constraint.
+    pub fn message<S: Into<String>>(mut self, message: S) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    /// The `path` method returns the path of the field.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// The `constraint` method returns the constraint the field violated.
+    pub fn constraint(&self) -> &Constraint {
+        &self.constraint
+    }
+
+    /// The `params` method returns the parameters of the constraint.
+    pub fn params(&self) -> &BTreeMap<String, String> {
+        &self.params
+    }
+
+    /// The `text` method returns the message set for the field, if any.
+    pub fn text(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+}
+
+impl std::fmt::Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path, self.constraint)?;
+        for (i, (key, value)) in self.params.iter().enumerate() {
+            let sep = if i == 0 { " (" } else { ", " };
+            write!(f, "{sep}{key} {value}")?;
+        }
+        if !self.params.is_empty() {
+            write!(f, ")")?;
+        }
+        if let Some(message) = &self.message {
+            write!(f, ": {message}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The `ValidationError` struct holds every field violation found in a form or request.
+#[derive(Debug, Clone, Default, PartialEq, Eq, thiserror::Error)]
+#[cfg_attr(any(feature = "serial", feature = "route"), derive(serde::Serialize))]
+pub struct ValidationError {
+    violations: Vec<Violation>,
+}
+
+impl ValidationError {
+    /// The `new` method creates an error with no violations.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The `push` method adds a violation.
+    pub fn push(&mut self, violation: Violation) {
+        self.violations.push(violation);
+    }
+
+    /// The `violation` method adds a violation and returns the error, for chaining.
+    pub fn violation(mut self, violation: Violation) -> Self {
+        self.push(violation);
+        self
+    }
+
+    /// The `nest` method adds the violations of `inner` under the field `prefix`, so a
+    /// violation of `zip` nested under `address` has the path `address.zip`, and one of `[3].qty`
+    /// nested under `items` has the path `items[3].qty`.  A violation of the inner value as a
+    /// whole, with an empty path, takes the path `prefix`.
+    pub fn nest(&mut self, prefix: &str, inner: ValidationError) {
+        for mut violation in inner.violations {
+            let dotted = !(violation.path.is_empty()
+                || prefix.is_empty()
+                || violation.path.starts_with('['));
+            violation.path = if dotted {
+                format!("{prefix}.{}", violation.path)
+            } else {
+                format!("{prefix}{}", violation.path)
+            };
+            self.violations.push(violation);
+        }
+    }
+
+    /// The `violations` method returns the violations.
+    pub fn violations(&self) -> &[Violation] {
+        &self.violations
+    }
+
+    /// The `is_empty` method returns `true` if no field is invalid.
+    pub fn is_empty(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// The `into_result` method returns `Ok` if no field is invalid, or the error as a
+    /// [`crate::prelude::Bandage::Validation`] otherwise.
+    pub fn into_result(self) -> Clean<()> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(self.into())
+        }
+    }
+
+    /// The `to_json` method returns the violations as JSON for a web form, in the form
+    /// `{"violations": [{"path": "address.zip", "code": "pattern", "params": {...}}]}`.
+    #[cfg(feature = "serial")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "serial")))]
+    pub fn to_json(&self) -> Clean<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+}
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} invalid field(s)", self.violations.len())?;
+        for violation in &self.violations {
+            write!(f, "\n  - {violation}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "route")]
+#[cfg_attr(docsrs, doc(cfg(feature = "route")))]
+impl axum::response::IntoResponse for ValidationError {
+    fn into_response(self) -> axum::response::Response {
+        (
+            axum::http::StatusCode::UNPROCESSABLE_ENTITY,
+            axum::Json(self),
+        )
+            .into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(errors: &ValidationError) -> Vec<&str> {
+        errors.violations().iter().map(Violation::path).collect()
+    }
+
+    #[test]
+    fn nest_joins_paths() {
+        let inner = ValidationError::new()
+            .violation(Violation::required("zip"))
+            .violation(Violation::range("[3].qty", 1, 99))
+            .violation(Violation::required(""));
+        let mut errors = ValidationError::new();
+        errors.nest("order", inner.clone());
+        assert_eq!(paths(&errors), ["order.zip", "order[3].qty", "order"]);
+        let mut errors = ValidationError::new();
+        errors.nest("", inner);
+        assert_eq!(paths(&errors), ["zip", "[3].qty", ""]);
+    }
+
+    #[test]
+    fn display_lists_each_violation() {
+        let errors = ValidationError::new()
+            .violation(Violation::required("name"))
+            .violation(Violation::range("qty", 1, 99).message("Order between 1 and 99"));
+        assert_eq!(
+            errors.to_string(),
+            "2 invalid field(s)\n  - name: required\n  - qty: range (max 99, min 1): Order between 1 and 99"
+        );
+        assert!(ValidationError::new().into_result().is_ok());
+        assert!(errors.into_result().is_err());
+    }
+
+    #[cfg(feature = "serial")]
+    #[test]
+    fn json_renames_the_constraint_and_skips_empty_members() {
+        let errors = ValidationError::new()
+            .violation(Violation::required("name"))
+            .violation(Violation::too_long("note", 80).message("Too long"))
+            .violation(Violation::new("id", Constraint::Other("taken".to_string())));
+        assert_eq!(
+            errors.to_json().unwrap(),
+            r#"{"violations":[{"path":"name","code":"required"},{"path":"note","code":"too_long","params":{"max":"80"},"message":"Too long"},{"path":"id","code":"taken"}]}"#
+        );
+    }
+
+    #[cfg(all(feature = "route", feature = "stream"))]
+    #[test]
+    fn response_is_unprocessable_entity() {
+        use axum::response::IntoResponse;
+        let response = ValidationError::new()
+            .violation(Violation::pattern("zip", r"^\d{5}$"))
+            .into_response();
+        assert_eq!(response.status(), 422);
+        assert_eq!(response.headers()["content-type"], "application/json");
+        let bytes =
+            futures::executor::block_on(axum::body::to_bytes(response.into_body(), usize::MAX))
+                .unwrap();
+        assert_eq!(
+            std::str::from_utf8(&bytes).unwrap(),
+            r#"{"violations":[{"path":"zip","code":"pattern","params":{"pattern":"^\\d{5}$"}}]}"#
+        );
+    }
+}